@@ -0,0 +1,193 @@
+//! One-off subcommands of the `inflyte` binary
+//!
+//! Results are printed to stdout; logs go to stderr.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use inflyte::diff::{EventKind, SupportEvent, diff_supports};
+use inflyte::monitor::fetch_campaign_page;
+use inflyte::notify::{event_marker, format_dj_line, format_event_line, summarize};
+use inflyte::storage::{self, DjStore};
+use inflyte::{Campaign, Config, DjSupport, health};
+
+/// The monitored campaign with the given name
+fn find_campaign<'a>(config: &'a Config, name: &str) -> Result<&'a Campaign> {
+    config
+        .campaigns
+        .iter()
+        .find(|c| c.name == name)
+        .with_context(|| {
+            let names: Vec<&str> = config.campaigns.iter().map(|c| c.name.as_str()).collect();
+            format!(
+                "Unknown campaign {} (monitoring: {})",
+                name,
+                names.join(", ")
+            )
+        })
+}
+
+fn open_store(config: &Config) -> Result<Arc<dyn DjStore>> {
+    storage::build_store(
+        &config.storage,
+        &config.blob_name_prefix,
+        &config.retention,
+        &config.names,
+    )
+}
+
+/// The current DJ list, or the given snapshot of it
+async fn load_djs(
+    store: &dyn DjStore,
+    campaign: &Campaign,
+    snapshot: Option<&str>,
+) -> Result<HashSet<DjSupport>> {
+    match snapshot {
+        Some(id) => store.load_snapshot(campaign, id).await,
+        None => store.load(campaign).await,
+    }
+}
+
+/// DJs ordered by name, for stable output
+fn sorted(djs: HashSet<DjSupport>) -> Vec<DjSupport> {
+    let mut djs: Vec<DjSupport> = djs.into_iter().collect();
+    djs.sort_by_key(|dj| dj.name.to_lowercase());
+    djs
+}
+
+/// Print a campaign's stored DJ list, one DJ per line
+pub async fn list(config: &Config, campaign: &str, snapshot: Option<&str>) -> Result<()> {
+    let campaign = find_campaign(config, campaign)?;
+    let store = open_store(config)?;
+    for dj in sorted(load_djs(store.as_ref(), campaign, snapshot).await?) {
+        println!("{}", format_dj_line(&dj));
+    }
+    Ok(())
+}
+
+/// Print how the campaign page differs from the stored DJ list, without saving or alerting
+pub async fn diff(config: &Config, campaign: &str, snapshot: Option<&str>) -> Result<()> {
+    let campaign = find_campaign(config, campaign)?;
+    let store = open_store(config)?;
+    let previous = load_djs(store.as_ref(), campaign, snapshot).await?;
+
+    let html = fetch_campaign_page(&campaign.url).await?;
+    let section = config.parser.parse(&html, &config.names);
+    for issue in health::assess(&section.stats, section.djs.len(), previous.len()) {
+        println!("⚠️ {}", issue);
+    }
+
+    let events = diff_supports(&previous, &section.djs, &config.names);
+    if events.is_empty() {
+        println!("No changes ({} DJs)", section.djs.len());
+        return Ok(());
+    }
+    for event in &events {
+        match event.kind {
+            EventKind::Removed => println!(
+                "{} {} (removed)",
+                event_marker(event.kind),
+                format_event_line(event)
+            ),
+            _ => println!("{} {}", event_marker(event.kind), format_event_line(event)),
+        }
+    }
+    println!("{}", summarize(&events));
+    Ok(())
+}
+
+/// Print everything stored for every campaign as one JSON document
+pub async fn export(config: &Config) -> Result<()> {
+    let store = open_store(config)?;
+    let mut campaigns = Vec::new();
+    for campaign in &config.campaigns {
+        campaigns.push(json!({
+            "name": campaign.name,
+            "url": campaign.url,
+            "metadata": store.load_metadata(campaign).await?,
+            "djs": sorted(store.load(campaign).await?),
+            "events": store.events(campaign).await?,
+        }));
+    }
+
+    let export = json!({
+        "exported_at": Utc::now().to_rfc3339(),
+        "campaigns": campaigns,
+    });
+    println!("{}", serde_json::to_string_pretty(&export)?);
+    Ok(())
+}
+
+/// Send a sample alert through each channel a campaign is routed to, reporting each result
+pub async fn test_notify(
+    config: &Config,
+    campaign: Option<&str>,
+    channel: Option<&str>,
+) -> Result<()> {
+    let campaign = match campaign {
+        Some(name) => find_campaign(config, name)?,
+        None => config
+            .campaigns
+            .first()
+            .context("No campaigns configured")?,
+    };
+    let store = open_store(config)?;
+    let campaign = Campaign {
+        metadata: store
+            .load_metadata(campaign)
+            .await?
+            .unwrap_or_else(|| campaign.metadata.clone()),
+        ..campaign.clone()
+    };
+
+    let mut channels = config.notifiers.routed(&campaign);
+    if let Some(channel) = channel {
+        if !channels.contains(&channel) {
+            anyhow::bail!(
+                "Channel {} is not enabled for campaign {}",
+                channel,
+                campaign.name
+            );
+        }
+        channels.retain(|c| *c == channel);
+    }
+    if channels.is_empty() {
+        anyhow::bail!(
+            "No notification channels are enabled for campaign {}",
+            campaign.name
+        );
+    }
+
+    let event = SupportEvent {
+        kind: EventKind::New,
+        dj: DjSupport {
+            name: "Inflyte Test DJ".to_string(),
+            comment: Some("Sample alert sent by inflyte test-notify".to_string()),
+            stars: Some(5),
+        },
+        previous: None,
+        detected_at: Utc::now().to_rfc3339(),
+    };
+
+    let mut failed = 0;
+    for channel in channels {
+        match config
+            .notifiers
+            .deliver(channel, None, &campaign, std::slice::from_ref(&event))
+            .await
+        {
+            Ok(()) => println!("✅ {}", channel),
+            Err(e) => {
+                failed += 1;
+                println!("❌ {}: {:#}", channel, e);
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{} channel(s) failed", failed);
+    }
+    Ok(())
+}
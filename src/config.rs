@@ -0,0 +1,184 @@
+use anyhow::{Context, Result};
+use chrono_tz::Tz;
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::digest::{Digest, DigestSchedule};
+use crate::metadata::CampaignMetadata;
+use crate::names::NameNormalizer;
+use crate::notify::Notifiers;
+use crate::outbox::Outbox;
+use crate::parser::{ExtractionRules, SupportParser};
+use crate::quiet::{self, QuietHours};
+use crate::rules::AlertRules;
+use crate::settings;
+use crate::storage::{self, RetentionPolicy, StorageConfig};
+use crate::{Campaign, extract_campaign_name};
+
+/// Everything the monitor needs, read from the environment
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub campaigns: Vec<Campaign>,
+    pub storage: StorageConfig,
+    pub blob_name_prefix: String,
+    pub retention: RetentionPolicy,
+    pub names: NameNormalizer,
+    pub parser: SupportParser,
+    pub notifiers: Notifiers,
+    /// Holds alerts until every channel has accepted them
+    pub outbox: Arc<Outbox>,
+    /// When digests are sent; `None` alerts on every change
+    pub digest: Option<Arc<Digest>>,
+    pub alert_on_removals: bool,
+    /// Global alert rules; campaigns may override them in their settings
+    pub alert_rules: AlertRules,
+    /// Hours during which alerts are held back; campaigns may override them
+    pub quiet_hours: Option<QuietHours>,
+    /// Timezone for quiet hours and digest times
+    pub timezone: Tz,
+    pub check_interval_minutes: u64,
+    pub http_port: u16,
+}
+
+impl Config {
+    pub fn from_env(urls: Vec<String>) -> Result<Self> {
+        let storage = StorageConfig::from_env()?;
+
+        let mut settings = match env::var("CAMPAIGN_SETTINGS_FILE") {
+            Ok(path) => settings::load_campaign_settings(&PathBuf::from(path))?,
+            Err(_) => Default::default(),
+        };
+
+        // Create campaign objects with extracted names
+        let campaigns: Vec<Campaign> = urls
+            .into_iter()
+            .map(|url| {
+                let name = extract_campaign_name(&url);
+                storage::check_campaign_name(&name)
+                    .with_context(|| format!("Can't monitor {}", url))?;
+                Ok(Campaign {
+                    settings: settings.remove(&name).unwrap_or_default(),
+                    url,
+                    name,
+                    metadata: CampaignMetadata::default(),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        for name in settings.keys() {
+            warn!(campaign = %name, "Campaign settings given for a campaign that isn't monitored");
+        }
+
+        let notifiers = Notifiers::from_env(&campaigns)?;
+        let timezone = quiet::timezone_from_env()?;
+
+        Ok(Config {
+            campaigns,
+            storage,
+            blob_name_prefix: env::var("AZURE_BLOB_NAME_PREFIX")
+                .unwrap_or_else(|_| "dj_list".to_string()),
+            retention: RetentionPolicy::from_env()?,
+            names: match env::var("DJ_ALIASES_FILE") {
+                Ok(path) => NameNormalizer::from_alias_file(&PathBuf::from(path))?,
+                Err(_) => NameNormalizer::default(),
+            },
+            parser: match env::var("EXTRACTION_RULES_FILE") {
+                Ok(path) => SupportParser::new(ExtractionRules::from_file(&PathBuf::from(path))?)?,
+                Err(_) => SupportParser::default(),
+            },
+            notifiers,
+            outbox: Arc::new(Outbox::from_env()?),
+            digest: DigestSchedule::from_env()?
+                .map(|schedule| Arc::new(Digest::new(schedule, timezone))),
+            alert_on_removals: env::var("ALERT_ON_REMOVALS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .context("ALERT_ON_REMOVALS must be true or false")?,
+            alert_rules: AlertRules::from_env()?,
+            quiet_hours: QuietHours::from_env()?,
+            timezone,
+            check_interval_minutes: env::var("CHECK_INTERVAL_MINUTES")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .context("CHECK_INTERVAL_MINUTES must be a valid number")?,
+            http_port: env::var("HTTP_PORT")
+                .unwrap_or_else(|_| "8080".to_string())
+                .parse()
+                .context("HTTP_PORT must be a valid number")?,
+        })
+    }
+
+    /// Log the configuration at startup
+    pub fn log_summary(&self) {
+        info!("Configuration:");
+        self.storage.log_summary();
+        info!("  Blob Name Prefix: {}", self.blob_name_prefix);
+        self.retention.log_summary();
+        info!("  DJ Aliases: {}", self.names.alias_count());
+        info!(
+            "  Support Section: \"{}\" / \"{}\"",
+            self.parser.rules().section_heading,
+            self.parser.rules().list_marker
+        );
+        self.notifiers.log_summary();
+        self.outbox.log_summary();
+        match &self.digest {
+            Some(digest) => info!("  Digest: {}", digest.describe()),
+            None => info!("  Digest: off (alerts are sent on every change)"),
+        }
+        info!("  Alert on Removals: {}", self.alert_on_removals);
+        info!("  Alert Rules: {}", self.alert_rules);
+        for campaign in &self.campaigns {
+            if let Some(rules) = &campaign.settings.alert_rules {
+                info!(
+                    "    {}: {}",
+                    campaign.name,
+                    self.alert_rules.with_overrides(rules)
+                );
+            }
+        }
+        info!(
+            "  Quiet Hours: {} ({})",
+            self.quiet_hours
+                .map_or("off".to_string(), |quiet| quiet.to_string()),
+            self.timezone
+        );
+        for campaign in &self.campaigns {
+            let settings = &campaign.settings;
+            if settings.quiet_hours.is_some() || settings.timezone.is_some() {
+                info!(
+                    "    {}: {} ({})",
+                    campaign.name,
+                    settings
+                        .quiet_hours
+                        .or(self.quiet_hours)
+                        .map_or("off".to_string(), |quiet| quiet.to_string()),
+                    settings.timezone.unwrap_or(self.timezone)
+                );
+            }
+            let recipients = settings
+                .recipients
+                .iter()
+                .chain(&settings.telegram_chat_ids);
+            for recipient in recipients {
+                if recipient.quiet_hours.is_some() || recipient.timezone.is_some() {
+                    let (quiet_hours, timezone) = settings.quiet_hours_for(
+                        Some(&recipient.to),
+                        self.quiet_hours,
+                        self.timezone,
+                    );
+                    info!(
+                        "    {} / {}: {} ({})",
+                        campaign.name,
+                        recipient.to,
+                        quiet_hours.map_or("off".to_string(), |quiet| quiet.to_string()),
+                        timezone
+                    );
+                }
+            }
+        }
+        info!("  Check Interval: {} minutes", self.check_interval_minutes);
+    }
+}
@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::DjSupport;
+use crate::names::NameNormalizer;
+
+/// What changed about a DJ's support between two checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// The DJ appeared in the Support section
+    New,
+    /// A DJ who was listed without a comment left one
+    CommentAdded,
+    /// A DJ changed (or removed) their comment
+    CommentEdited,
+    /// A DJ changed (or removed) their star rating
+    RatingChanged,
+    /// The DJ disappeared from the Support section
+    Removed,
+}
+
+impl EventKind {
+    /// Whether the event is a change to an existing support rather than an addition/removal
+    pub fn is_update(self) -> bool {
+        matches!(
+            self,
+            EventKind::CommentAdded | EventKind::CommentEdited | EventKind::RatingChanged
+        )
+    }
+}
+
+/// A change detected in a campaign's Support section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupportEvent {
+    pub kind: EventKind,
+    /// The DJ as currently listed (as last listed, for removals)
+    pub dj: DjSupport,
+    /// The DJ as previously listed, for updates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<DjSupport>,
+    pub detected_at: String,
+}
+
+/// Index DJs by identity after merging listings that refer to the same DJ
+fn index_by_identity(
+    djs: &HashSet<DjSupport>,
+    names: &NameNormalizer,
+) -> HashMap<String, DjSupport> {
+    names
+        .merge(djs.clone())
+        .into_iter()
+        .map(|dj| (names.identity(&dj.name), dj))
+        .collect()
+}
+
+/// Compare the previous and current DJ lists by DJ identity (see [`NameNormalizer`])
+///
+/// A DJ whose comment and rating both changed yields one event for each. Events are
+/// ordered by kind, then by DJ name.
+pub fn diff_supports(
+    previous: &HashSet<DjSupport>,
+    current: &HashSet<DjSupport>,
+    names: &NameNormalizer,
+) -> Vec<SupportEvent> {
+    let detected_at = chrono::Utc::now().to_rfc3339();
+    let previous = index_by_identity(previous, names);
+    let current = index_by_identity(current, names);
+
+    let mut events = Vec::new();
+    let mut push = |kind: EventKind, dj: &DjSupport, previous: Option<&DjSupport>| {
+        events.push(SupportEvent {
+            kind,
+            dj: dj.clone(),
+            previous: previous.cloned(),
+            detected_at: detected_at.clone(),
+        });
+    };
+
+    for (identity, dj) in &current {
+        let Some(prev) = previous.get(identity) else {
+            push(EventKind::New, dj, None);
+            continue;
+        };
+
+        match (&prev.comment, &dj.comment) {
+            (None, Some(_)) => push(EventKind::CommentAdded, dj, Some(prev)),
+            (Some(old), new) if Some(old) != new.as_ref() => {
+                push(EventKind::CommentEdited, dj, Some(prev))
+            }
+            _ => {}
+        }
+
+        if prev.stars != dj.stars {
+            push(EventKind::RatingChanged, dj, Some(prev));
+        }
+    }
+
+    for (identity, dj) in &previous {
+        if !current.contains_key(identity) {
+            push(EventKind::Removed, dj, None);
+        }
+    }
+
+    events.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.dj.name.cmp(&b.dj.name)));
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::dj;
+
+    #[test]
+    fn support_changes() {
+        use EventKind::*;
+
+        /// Name, previous list, current list and the expected events
+        type Case = (
+            &'static str,
+            Vec<DjSupport>,
+            Vec<DjSupport>,
+            Vec<(EventKind, &'static str)>,
+        );
+        let cases: Vec<Case> = vec![
+            (
+                "unchanged",
+                vec![dj("Dixon", Some("Nice"), Some(4))],
+                vec![dj("Dixon", Some("Nice"), Some(4))],
+                vec![],
+            ),
+            (
+                "new",
+                vec![],
+                vec![dj("Dixon", None, None)],
+                vec![(New, "Dixon")],
+            ),
+            (
+                "removed",
+                vec![dj("Dixon", None, None)],
+                vec![],
+                vec![(Removed, "Dixon")],
+            ),
+            (
+                "comment added",
+                vec![dj("Dixon", None, None)],
+                vec![dj("Dixon", Some("Nice"), None)],
+                vec![(CommentAdded, "Dixon")],
+            ),
+            (
+                "comment edited",
+                vec![dj("Dixon", Some("Nice"), None)],
+                vec![dj("Dixon", Some("Great"), None)],
+                vec![(CommentEdited, "Dixon")],
+            ),
+            (
+                "comment removed",
+                vec![dj("Dixon", Some("Nice"), None)],
+                vec![dj("Dixon", None, None)],
+                vec![(CommentEdited, "Dixon")],
+            ),
+            (
+                "rating changed",
+                vec![dj("Dixon", None, Some(3))],
+                vec![dj("Dixon", None, Some(5))],
+                vec![(RatingChanged, "Dixon")],
+            ),
+            (
+                "rating added",
+                vec![dj("Dixon", None, None)],
+                vec![dj("Dixon", None, Some(5))],
+                vec![(RatingChanged, "Dixon")],
+            ),
+            (
+                "comment and rating",
+                vec![dj("Dixon", Some("Nice"), Some(3))],
+                vec![dj("Dixon", Some("Great"), Some(5))],
+                vec![(CommentEdited, "Dixon"), (RatingChanged, "Dixon")],
+            ),
+            // Spelling variants of the same DJ are matched by identity
+            (
+                "renamed variant",
+                vec![dj("DJ Tennis", None, None)],
+                vec![dj("Tennis 🔥", None, None)],
+                vec![],
+            ),
+            (
+                "variant with comment",
+                vec![dj("Âme (Innervisions)", None, None)],
+                vec![dj("AME", Some("Nice"), None)],
+                vec![(CommentAdded, "AME")],
+            ),
+            (
+                "ordered by kind then name",
+                vec![dj("Kölsch", None, None), dj("Dixon", None, Some(3))],
+                vec![
+                    dj("Solomun", None, None),
+                    dj("Adam Beyer", None, None),
+                    dj("Dixon", None, Some(4)),
+                ],
+                vec![
+                    (New, "Adam Beyer"),
+                    (New, "Solomun"),
+                    (RatingChanged, "Dixon"),
+                    (Removed, "Kölsch"),
+                ],
+            ),
+        ];
+
+        let names = NameNormalizer::default();
+        for (case, previous, current, expected) in cases {
+            let events = diff_supports(
+                &previous.into_iter().collect(),
+                &current.into_iter().collect(),
+                &names,
+            );
+            let actual: Vec<(EventKind, &str)> = events
+                .iter()
+                .map(|e| (e.kind, e.dj.name.as_str()))
+                .collect();
+            assert_eq!(actual, expected, "{}", case);
+        }
+    }
+
+    #[test]
+    fn updates_keep_the_previous_listing() {
+        let names = NameNormalizer::default();
+        let events = diff_supports(
+            &HashSet::from([dj("Dixon", Some("Nice"), None)]),
+            &HashSet::from([dj("Dixon", Some("Great"), None)]),
+            &names,
+        );
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].dj.comment.as_deref(), Some("Great"));
+        assert_eq!(
+            events[0]
+                .previous
+                .as_ref()
+                .and_then(|p| p.comment.as_deref()),
+            Some("Nice")
+        );
+        assert!(events[0].kind.is_update());
+    }
+}
@@ -0,0 +1,343 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, DurationRound, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+
+use crate::Campaign;
+use crate::diff::SupportEvent;
+use crate::quiet::local_to_utc;
+
+/// When digests are sent (hours in the configured timezone)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestSchedule {
+    /// At the top of every hour
+    Hourly,
+    /// Every day at the given hour
+    Daily { hour: u32 },
+    /// Every week on the given day, at the given hour
+    Weekly { weekday: Weekday, hour: u32 },
+}
+
+impl DigestSchedule {
+    /// Read `DIGEST_SCHEDULE` (`hourly`, `daily` or `weekly`), `DIGEST_HOUR` and
+    /// `DIGEST_WEEKDAY`; digests are off unless a schedule is set
+    pub fn from_env() -> Result<Option<Self>> {
+        let schedule = match env::var("DIGEST_SCHEDULE") {
+            Ok(schedule) => schedule.to_lowercase(),
+            Err(_) => return Ok(None),
+        };
+
+        let hour = || -> Result<u32> {
+            let hour: u32 = env::var("DIGEST_HOUR")
+                .unwrap_or_else(|_| "9".to_string())
+                .parse()
+                .context("DIGEST_HOUR must be a number")?;
+            if hour > 23 {
+                anyhow::bail!("DIGEST_HOUR must be between 0 and 23");
+            }
+            Ok(hour)
+        };
+
+        match schedule.as_str() {
+            "off" | "" => Ok(None),
+            "hourly" => Ok(Some(DigestSchedule::Hourly)),
+            "daily" => Ok(Some(DigestSchedule::Daily { hour: hour()? })),
+            "weekly" => {
+                let weekday = env::var("DIGEST_WEEKDAY")
+                    .unwrap_or_else(|_| "monday".to_string())
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("DIGEST_WEEKDAY must be a day of the week"))?;
+                Ok(Some(DigestSchedule::Weekly {
+                    weekday,
+                    hour: hour()?,
+                }))
+            }
+            other => anyhow::bail!(
+                "Invalid DIGEST_SCHEDULE '{}': expected hourly, daily, weekly or off",
+                other
+            ),
+        }
+    }
+
+    /// The latest send time at or before `now`
+    pub fn last_at_or_before(self, now: DateTime<Utc>, tz: Tz) -> DateTime<Utc> {
+        let next = self.next_after(now, tz);
+        let step = match self {
+            DigestSchedule::Hourly => Duration::hours(1),
+            DigestSchedule::Daily { .. } => Duration::days(1),
+            DigestSchedule::Weekly { .. } => Duration::weeks(1),
+        };
+        // Stepping back in local time keeps the hour across DST changes
+        let local = next.with_timezone(&tz).naive_local() - step;
+        match self {
+            DigestSchedule::Hourly => next - step,
+            _ => local_to_utc(tz, local),
+        }
+    }
+
+    /// The first send time strictly after `now`
+    pub fn next_after(self, now: DateTime<Utc>, tz: Tz) -> DateTime<Utc> {
+        let today = now.with_timezone(&tz).date_naive();
+        let at_hour = |date: NaiveDate, hour: u32| {
+            local_to_utc(
+                tz,
+                date.and_time(NaiveTime::from_hms_opt(hour, 0, 0).expect("hour is below 24")),
+            )
+        };
+
+        match self {
+            DigestSchedule::Hourly => {
+                now.duration_trunc(Duration::hours(1))
+                    .expect("an hour fits any timestamp")
+                    + Duration::hours(1)
+            }
+            DigestSchedule::Daily { hour } => {
+                let candidate = at_hour(today, hour);
+                if candidate > now {
+                    candidate
+                } else {
+                    at_hour(today + Duration::days(1), hour)
+                }
+            }
+            DigestSchedule::Weekly { weekday, hour } => {
+                let days_ahead = (7 + weekday.num_days_from_monday()
+                    - today.weekday().num_days_from_monday())
+                    % 7;
+                let date = today + Duration::days(days_ahead as i64);
+                let candidate = at_hour(date, hour);
+                if candidate > now {
+                    candidate
+                } else {
+                    at_hour(date + Duration::weeks(1), hour)
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for DigestSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DigestSchedule::Hourly => write!(f, "hourly"),
+            DigestSchedule::Daily { hour } => write!(f, "daily at {:02}:00", hour),
+            DigestSchedule::Weekly { weekday, hour } => {
+                write!(f, "weekly on {} at {:02}:00", weekday, hour)
+            }
+        }
+    }
+}
+
+/// The changes of one campaign collected for a digest
+#[derive(Debug, Clone)]
+pub struct DigestEntry {
+    /// The campaign as of its latest check
+    pub campaign: Campaign,
+    pub events: Vec<SupportEvent>,
+}
+
+/// Everything that changed across campaigns since the previous digest
+#[derive(Debug, Clone)]
+pub struct DigestReport {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub entries: Vec<DigestEntry>,
+}
+
+impl DigestReport {
+    /// An empty report covering the same period
+    pub fn clone_period(&self) -> DigestReport {
+        DigestReport {
+            since: self.since,
+            until: self.until,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Number of events across all campaigns
+    pub fn event_count(&self) -> usize {
+        self.entries.iter().map(|e| e.events.len()).sum()
+    }
+
+    /// One digest of changes collected in the given periods, e.g. a digest that failed to
+    /// go out yesterday along with today's; a campaign's changes are listed together
+    pub fn collect<'a>(
+        parts: impl IntoIterator<Item = (&'a DigestPeriod, Campaign, &'a [SupportEvent])>,
+    ) -> Self {
+        let mut since: Option<DateTime<Utc>> = None;
+        let mut until: Option<DateTime<Utc>> = None;
+        let mut entries: Vec<DigestEntry> = Vec::new();
+        for (period, campaign, events) in parts {
+            let (start, end) = period.bounds();
+            since = Some(since.map_or(start, |since| since.min(start)));
+            until = Some(until.map_or(end, |until| until.max(end)));
+            match entries
+                .iter_mut()
+                .find(|e| e.campaign.name == campaign.name)
+            {
+                Some(entry) => {
+                    entry.campaign = campaign;
+                    entry.events.extend_from_slice(events);
+                }
+                None => entries.push(DigestEntry {
+                    campaign,
+                    events: events.to_vec(),
+                }),
+            }
+        }
+
+        let now = Utc::now();
+        DigestReport {
+            since: since.unwrap_or(now),
+            until: until.unwrap_or(now),
+            entries,
+        }
+    }
+}
+
+/// The period whose changes go into one digest, sent at its end
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestPeriod {
+    pub since: String,
+    pub until: String,
+}
+
+impl DigestPeriod {
+    /// When the digest is due
+    pub fn until(&self) -> DateTime<Utc> {
+        self.bounds().1
+    }
+
+    fn bounds(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        let parse = |at: &str| {
+            DateTime::parse_from_rfc3339(at)
+                .map(|at| at.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now())
+        };
+        (parse(&self.since), parse(&self.until))
+    }
+}
+
+/// When digests are sent
+///
+/// Changes waiting for a digest are stored in the outbox, due at the end of the period
+/// they were detected in, so they survive restarts and failed sends are retried.
+#[derive(Debug)]
+pub struct Digest {
+    schedule: DigestSchedule,
+    timezone: Tz,
+}
+
+impl Digest {
+    pub fn new(schedule: DigestSchedule, timezone: Tz) -> Self {
+        Digest { schedule, timezone }
+    }
+
+    /// The schedule with its timezone, for logging
+    pub fn describe(&self) -> String {
+        format!("{} {}", self.schedule, self.timezone)
+    }
+
+    /// The period changes detected at `now` are sent with
+    pub fn period(&self, now: DateTime<Utc>) -> DigestPeriod {
+        DigestPeriod {
+            since: self
+                .schedule
+                .last_at_or_before(now, self.timezone)
+                .to_rfc3339(),
+            until: self.schedule.next_after(now, self.timezone).to_rfc3339(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn next_digest_times() {
+        // A Wednesday
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 10, 30, 0).unwrap();
+        let at = |d, h| Utc.with_ymd_and_hms(2025, 1, d, h, 0, 0).unwrap();
+
+        assert_eq!(DigestSchedule::Hourly.next_after(now, Tz::UTC), at(15, 11));
+        assert_eq!(
+            DigestSchedule::Hourly.next_after(at(15, 11), Tz::UTC),
+            at(15, 12)
+        );
+        assert_eq!(
+            DigestSchedule::Daily { hour: 9 }.next_after(now, Tz::UTC),
+            at(16, 9)
+        );
+        assert_eq!(
+            DigestSchedule::Daily { hour: 18 }.next_after(now, Tz::UTC),
+            at(15, 18)
+        );
+        assert_eq!(
+            DigestSchedule::Weekly {
+                weekday: Weekday::Mon,
+                hour: 9
+            }
+            .next_after(now, Tz::UTC),
+            at(20, 9)
+        );
+        assert_eq!(
+            DigestSchedule::Weekly {
+                weekday: Weekday::Wed,
+                hour: 9
+            }
+            .next_after(now, Tz::UTC),
+            at(22, 9)
+        );
+        assert_eq!(
+            DigestSchedule::Weekly {
+                weekday: Weekday::Wed,
+                hour: 11
+            }
+            .next_after(now, Tz::UTC),
+            at(15, 11)
+        );
+
+        // Hours are local: 09:00 in Berlin is 08:00 UTC in winter
+        let berlin: Tz = "Europe/Berlin".parse().unwrap();
+        assert_eq!(
+            DigestSchedule::Daily { hour: 9 }.next_after(now, berlin),
+            at(16, 8)
+        );
+    }
+
+    #[test]
+    fn digest_periods() {
+        // A Wednesday
+        let now = Utc.with_ymd_and_hms(2025, 1, 15, 10, 30, 0).unwrap();
+        let at = |d, h| Utc.with_ymd_and_hms(2025, 1, d, h, 0, 0).unwrap();
+
+        assert_eq!(
+            DigestSchedule::Hourly.last_at_or_before(now, Tz::UTC),
+            at(15, 10)
+        );
+        assert_eq!(
+            DigestSchedule::Hourly.last_at_or_before(at(15, 10), Tz::UTC),
+            at(15, 10)
+        );
+        assert_eq!(
+            DigestSchedule::Daily { hour: 9 }.last_at_or_before(now, Tz::UTC),
+            at(15, 9)
+        );
+        assert_eq!(
+            DigestSchedule::Weekly {
+                weekday: Weekday::Mon,
+                hour: 9
+            }
+            .last_at_or_before(now, Tz::UTC),
+            at(13, 9)
+        );
+
+        let digest = Digest::new(DigestSchedule::Daily { hour: 18 }, Tz::UTC);
+        let period = digest.period(now);
+        assert_eq!(period.since, at(14, 18).to_rfc3339());
+        assert_eq!(period.until(), at(15, 18));
+    }
+}
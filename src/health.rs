@@ -0,0 +1,112 @@
+use std::fmt;
+
+use crate::parser::ParseStats;
+
+/// A sign that the campaign page no longer matches what the parser expects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthIssue {
+    /// The `<h3>Support</h3>` heading wasn't found
+    MissingHeading,
+    /// The Support section has profile pictures but no card could be parsed
+    UnparsedProfiles { images: usize },
+    /// A "Support from" list was found but no names could be extracted from it
+    UnparsedSupportList,
+    /// DJs were stored from the last check but none were found now
+    DroppedToZero { previous: usize },
+}
+
+impl fmt::Display for HealthIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthIssue::MissingHeading => write!(f, "Support heading not found on the page"),
+            HealthIssue::UnparsedProfiles { images } => write!(
+                f,
+                "{} profile picture(s) found but no profile cards could be parsed",
+                images
+            ),
+            HealthIssue::UnparsedSupportList => {
+                write!(
+                    f,
+                    "\"Support from\" list found but no names could be parsed"
+                )
+            }
+            HealthIssue::DroppedToZero { previous } => {
+                write!(f, "DJ count dropped from {} to 0", previous)
+            }
+        }
+    }
+}
+
+/// Judge whether a check can be trusted, given what the parser saw and how many DJs the
+/// campaign had at the previous check
+///
+/// Any issue marks the check as suspect: its result must not replace the stored DJ list.
+pub fn assess(stats: &ParseStats, current: usize, previous: usize) -> Vec<HealthIssue> {
+    let mut issues = Vec::new();
+
+    if !stats.heading_found {
+        issues.push(HealthIssue::MissingHeading);
+    } else {
+        if stats.profile_images > 0 && stats.profile_cards == 0 {
+            issues.push(HealthIssue::UnparsedProfiles {
+                images: stats.profile_images,
+            });
+        }
+        if stats.list_found && stats.list_names == 0 {
+            issues.push(HealthIssue::UnparsedSupportList);
+        }
+    }
+
+    // A new campaign legitimately has no supports yet
+    if current == 0 && previous > 0 {
+        issues.push(HealthIssue::DroppedToZero { previous });
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(heading_found: bool, images: usize, cards: usize, list: Option<usize>) -> ParseStats {
+        ParseStats {
+            heading_found,
+            profile_images: images,
+            profile_cards: cards,
+            list_found: list.is_some(),
+            list_names: list.unwrap_or(0),
+        }
+    }
+
+    #[test]
+    fn assess_parse_quality() {
+        use HealthIssue::*;
+
+        // Healthy pages, including a new campaign without supports yet
+        assert_eq!(assess(&stats(true, 3, 3, Some(5)), 8, 7), vec![]);
+        assert_eq!(assess(&stats(true, 0, 0, None), 0, 0), vec![]);
+        assert_eq!(assess(&stats(true, 0, 0, Some(2)), 2, 0), vec![]);
+
+        assert_eq!(
+            assess(&stats(false, 0, 0, None), 0, 0),
+            vec![MissingHeading]
+        );
+        assert_eq!(
+            assess(&stats(false, 0, 0, None), 0, 4),
+            vec![MissingHeading, DroppedToZero { previous: 4 }]
+        );
+        assert_eq!(
+            assess(&stats(true, 0, 0, None), 0, 4),
+            vec![DroppedToZero { previous: 4 }]
+        );
+        assert_eq!(
+            assess(&stats(true, 3, 0, Some(2)), 2, 5),
+            vec![UnparsedProfiles { images: 3 }]
+        );
+        assert_eq!(
+            assess(&stats(true, 2, 2, Some(0)), 2, 2),
+            vec![UnparsedSupportList]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Monitors Inflyte campaign pages for DJ support and alerts on changes
+//!
+//! The `inflyte` binary is a thin CLI around this crate; services that want to embed the
+//! monitor can use the same pieces:
+//!
+//! * [`parser`] and [`metadata`] scrape the Support section and release details of a page
+//! * [`diff`] compares two DJ lists into support events
+//! * [`storage`] persists DJ lists, snapshots and event logs ([`storage::DjStore`])
+//! * [`notify`] delivers alerts ([`notify::Notifier`], [`notify::Notifiers`])
+//! * [`monitor`] ties them together: one check with [`monitor::check_for_new_djs`], every
+//!   campaign once with [`monitor::run_once`], or the whole service with [`monitor::run`]
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! let urls = vec!["https://inflyteapp.com/r/pmqtne".to_string()];
+//! let config = inflyte::Config::from_env(urls)?;
+//! inflyte::monitor::run(config, true).await
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+pub mod config;
+pub mod diff;
+pub mod digest;
+pub mod health;
+pub mod metadata;
+pub mod monitor;
+pub mod names;
+pub mod notify;
+pub mod outbox;
+pub mod parser;
+pub mod quiet;
+pub mod rules;
+pub mod server;
+pub mod settings;
+pub mod storage;
+#[cfg(test)]
+mod testing;
+
+pub use config::Config;
+
+use metadata::CampaignMetadata;
+use settings::CampaignSettings;
+
+/// A monitored Inflyte campaign page
+#[derive(Debug, Clone, Serialize)]
+pub struct Campaign {
+    pub url: String,
+    pub name: String,
+    pub metadata: CampaignMetadata,
+    #[serde(skip)]
+    pub settings: CampaignSettings,
+}
+
+impl Campaign {
+    /// "Artist - Title" when known, otherwise the campaign name
+    pub fn display_name(&self) -> String {
+        self.metadata
+            .track_title()
+            .unwrap_or_else(|| self.name.clone())
+    }
+}
+
+/// One DJ listed in a campaign's Support section
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct DjSupport {
+    pub name: String,
+    pub comment: Option<String>,
+    pub stars: Option<u8>,
+}
+
+/// A stored DJ list
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DjStorage {
+    pub djs: HashSet<DjSupport>,
+}
+
+/// Extract campaign name from URL (e.g., `https://inflyteapp.com/r/pmqtne` -> `pmqtne`)
+pub fn extract_campaign_name(url: &str) -> String {
+    url.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or("unknown")
+        .to_string()
+}
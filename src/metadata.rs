@@ -0,0 +1,263 @@
+use scraper::{Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+
+/// Labels introducing each release detail on a campaign page, matched case-insensitively
+const LABEL_KEYS: &[&str] = &["label", "record label"];
+const CATALOGUE_KEYS: &[&str] = &[
+    "catalogue number",
+    "catalog number",
+    "catalogue no",
+    "catalogue no.",
+    "catalogue",
+    "cat no",
+    "cat no.",
+    "cat. no.",
+    "cat#",
+];
+const RELEASE_DATE_KEYS: &[&str] = &["release date", "released"];
+const GENRE_KEYS: &[&str] = &["genre", "genres"];
+
+/// Headings of the tracklist, whose entries name the remixes
+const TRACKLIST_HEADINGS: &[&str] = &["tracks", "tracklist", "tracklisting"];
+
+/// Words marking a bracketed suffix of a track name as a version ("Lumen (Vela Remix)")
+const VERSION_WORDS: &[&str] = &["remix", "mix", "edit", "dub", "rework", "version", "vip"];
+
+/// Release details shown on a campaign page; every field is optional since pages differ
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CampaignMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Versions on the release other than the original mix
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remixes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub catalogue_number: Option<String>,
+    /// As printed on the page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artwork_url: Option<String>,
+}
+
+impl CampaignMetadata {
+    /// Extract release details from a campaign page
+    ///
+    /// The track comes from the first `h1` reading "Artist - Title", remixes from the
+    /// tracklist, artwork from the `og:image` tag (or an image marked as artwork/cover),
+    /// and label, catalogue number, release date and genre from "Label: value" text or
+    /// label/value element pairs such as `<dt>Label</dt><dd>value</dd>`.
+    pub fn parse(html: &str) -> Self {
+        let document = Html::parse_document(html);
+        let mut metadata = CampaignMetadata::default();
+
+        let h1_selector = Selector::parse("h1").unwrap();
+        let track = document
+            .select(&h1_selector)
+            .map(|h1| h1.text().collect::<String>().trim().to_string())
+            // Skip empty headings and the site's own branding
+            .find(|text| !text.is_empty() && text.contains('-') && !text.contains("Inflyte"));
+        if let Some(track) = &track {
+            match track.split_once(" - ").or_else(|| track.split_once('-')) {
+                Some((artist, title)) => {
+                    metadata.artist = non_empty(artist);
+                    metadata.title = non_empty(title);
+                }
+                None => metadata.title = non_empty(track),
+            }
+        }
+
+        // Remixes from the tracklist, or from the track heading when there is none
+        let mut track_names = tracklist(&document);
+        if track_names.is_empty() {
+            track_names.extend(metadata.title.clone());
+        }
+        for name in track_names {
+            if let Some(version) = version_of(&name)
+                && !version.eq_ignore_ascii_case("original mix")
+                && !metadata.remixes.contains(&version)
+            {
+                metadata.remixes.push(version);
+            }
+        }
+
+        metadata.artwork_url = artwork_url(&document);
+
+        let texts = text_nodes(&document);
+        metadata.label = labeled_value(&texts, LABEL_KEYS);
+        metadata.catalogue_number = labeled_value(&texts, CATALOGUE_KEYS);
+        metadata.release_date = labeled_value(&texts, RELEASE_DATE_KEYS);
+        metadata.genre = labeled_value(&texts, GENRE_KEYS);
+
+        metadata
+    }
+
+    /// Whether nothing could be extracted from the page
+    pub fn is_empty(&self) -> bool {
+        *self == CampaignMetadata::default()
+    }
+
+    /// "Artist - Title", or whichever of the two is known
+    pub fn track_title(&self) -> Option<String> {
+        match (&self.artist, &self.title) {
+            (Some(artist), Some(title)) => Some(format!("{} - {}", artist, title)),
+            (artist, title) => title.clone().or_else(|| artist.clone()),
+        }
+    }
+
+    /// Label, catalogue number, release date and genre as (caption, value) pairs, in
+    /// display order, skipping unknown ones
+    pub fn release_details(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("Label", &self.label),
+            ("Catalogue", &self.catalogue_number),
+            ("Release Date", &self.release_date),
+            ("Genre", &self.genre),
+        ]
+        .into_iter()
+        .filter_map(|(caption, value)| Some((caption, value.as_deref()?)))
+        .collect()
+    }
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Track names listed under a "Tracks"/"Tracklist" heading, up to the next heading
+fn tracklist(document: &Html) -> Vec<String> {
+    let heading_selector = Selector::parse("h2, h3, h4").unwrap();
+    let item_selector = Selector::parse("li").unwrap();
+
+    let Some(heading) = document.select(&heading_selector).find(|h| {
+        let text = h.text().collect::<String>().trim().to_lowercase();
+        TRACKLIST_HEADINGS.contains(&text.as_str())
+    }) else {
+        return Vec::new();
+    };
+
+    let mut names = Vec::new();
+    for sibling in heading.next_siblings() {
+        let Some(elem) = scraper::ElementRef::wrap(sibling) else {
+            continue;
+        };
+        if heading_selector.matches(&elem) {
+            break;
+        }
+
+        let items: Vec<String> = elem
+            .select(&item_selector)
+            .map(|li| li.text().collect::<String>())
+            .collect();
+        let texts = if items.is_empty() {
+            vec![elem.text().collect::<String>()]
+        } else {
+            items
+        };
+        names.extend(
+            texts
+                .iter()
+                .flat_map(|text| text.lines())
+                .filter_map(non_empty),
+        );
+    }
+    names
+}
+
+/// The bracketed version suffix of a track name, if it names a mix, edit or remix
+fn version_of(track: &str) -> Option<String> {
+    let inner = track.trim().strip_suffix(')')?;
+    let start = inner.rfind('(')?;
+    let version = inner[start + 1..].trim();
+    let lower = version.to_lowercase();
+    lower
+        .split_whitespace()
+        .any(|word| VERSION_WORDS.contains(&word))
+        .then(|| version.to_string())
+}
+
+fn artwork_url(document: &Html) -> Option<String> {
+    let og_selector = Selector::parse(r#"meta[property="og:image"]"#).unwrap();
+    if let Some(url) = document
+        .select(&og_selector)
+        .find_map(|meta| non_empty(meta.value().attr("content")?))
+    {
+        return Some(url);
+    }
+
+    let img_selector = Selector::parse("img").unwrap();
+    document.select(&img_selector).find_map(|img| {
+        let attrs = ["alt", "class", "id", "src"]
+            .iter()
+            .filter_map(|attr| img.value().attr(attr))
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        if attrs.contains("artwork") || attrs.contains("cover") {
+            non_empty(img.value().attr("src")?)
+        } else {
+            None
+        }
+    })
+}
+
+/// Every non-empty piece of text in the page body, in document order
+fn text_nodes(document: &Html) -> Vec<String> {
+    let body_selector = Selector::parse("body").unwrap();
+    let Some(body) = document.select(&body_selector).next() else {
+        return Vec::new();
+    };
+
+    body.descendants()
+        .filter_map(|node| match node.value() {
+            Node::Text(text) => non_empty(text),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The value following one of `keys`, either on the same text ("Genre: Techno") or as the
+/// next piece of text ("<dt>Genre</dt><dd>Techno</dd>")
+fn labeled_value(texts: &[String], keys: &[&str]) -> Option<String> {
+    texts.iter().enumerate().find_map(|(index, text)| {
+        let (key, value) = match text.split_once(':') {
+            Some((key, value)) => (key, value.trim()),
+            None => (text.as_str(), ""),
+        };
+        if !keys.contains(&key.trim().to_lowercase().as_str()) {
+            return None;
+        }
+
+        if value.is_empty() {
+            non_empty(texts.get(index + 1)?)
+        } else {
+            non_empty(value)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Metadata extracted from every page saved under `tests/fixtures`
+    #[test]
+    fn campaign_metadata_fixtures() {
+        insta::with_settings!({
+            snapshot_path => "../tests/snapshots",
+            prepend_module_to_snapshot => false,
+        }, {
+            insta::glob!("../tests/fixtures", "*.html", |path| {
+                let html = std::fs::read_to_string(path).unwrap();
+                insta::assert_json_snapshot!(CampaignMetadata::parse(&html));
+            });
+        });
+    }
+}
@@ -0,0 +1,716 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::time;
+use tracing::{debug, error, info, warn};
+
+use crate::diff::{EventKind, SupportEvent, diff_supports};
+use crate::digest::DigestPeriod;
+use crate::health;
+use crate::metadata::CampaignMetadata;
+use crate::notify::{format_dj_line, format_event_line};
+use crate::outbox::{Delivery, DeliveryReport};
+use crate::parser::SupportSection;
+use crate::rules::AlertRules;
+use crate::server::{AppState, start_http_server, update_campaign_stats};
+use crate::storage::{self, DjStore};
+use crate::{Campaign, Config};
+
+/// How a campaign's check went
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// First check, the DJ list was stored without alerting
+    Initial,
+    Unchanged,
+    Changed,
+    /// The page didn't parse as expected, the stored DJ list was kept
+    Suspect,
+    /// The check failed, e.g. the page or storage couldn't be reached
+    Failed,
+}
+
+/// What a check of one campaign found
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckOutcome {
+    pub status: CheckStatus,
+    /// DJs in the stored list after the check; unknown when the check failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub djs: Option<usize>,
+    pub new: usize,
+    pub updated: usize,
+    pub removed: usize,
+    /// Changes that matched the alert rules and were queued for delivery
+    pub alerts: usize,
+}
+
+impl CheckOutcome {
+    fn without_changes(status: CheckStatus, djs: Option<usize>) -> Self {
+        CheckOutcome {
+            status,
+            djs,
+            new: 0,
+            updated: 0,
+            removed: 0,
+            alerts: 0,
+        }
+    }
+}
+
+/// The outcome of one campaign in a [`RunSummary`]
+#[derive(Debug, Clone, Serialize)]
+pub struct CampaignSummary {
+    pub campaign: String,
+    #[serde(flatten)]
+    pub outcome: CheckOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// What [`run_once`] did, printed as JSON by `inflyte check --once`
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub started_at: String,
+    pub finished_at: String,
+    pub campaigns: Vec<CampaignSummary>,
+    pub outbox: DeliveryReport,
+}
+
+impl RunSummary {
+    /// Process exit status: 0 when everything went fine, 2 when a campaign failed or
+    /// looks suspect, 3 when alerts couldn't be delivered
+    pub fn exit_code(&self) -> u8 {
+        let check_failed = self
+            .campaigns
+            .iter()
+            .any(|c| matches!(c.outcome.status, CheckStatus::Failed | CheckStatus::Suspect));
+        if check_failed {
+            2
+        } else if self.outbox.error.is_some() || self.outbox.failed + self.outbox.dead_lettered > 0
+        {
+            3
+        } else {
+            0
+        }
+    }
+}
+
+/// Extract the track and release details from the webpage
+pub async fn fetch_metadata(url: &str) -> Option<CampaignMetadata> {
+    debug!(url = %url, "Fetching campaign metadata");
+
+    // Create a client with timeout
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+    {
+        Ok(c) => c,
+        Err(e) => {
+            warn!(error = %e, "Failed to create HTTP client");
+            return None;
+        }
+    };
+
+    // Fetch the page with timeout
+    let response = match client.get(url).send().await {
+        Ok(r) => r,
+        Err(e) => {
+            warn!(url = %url, error = %e, "Failed to fetch page");
+            return None;
+        }
+    };
+
+    let text = match response.text().await {
+        Ok(t) => t,
+        Err(e) => {
+            warn!(error = %e, "Failed to read response text");
+            return None;
+        }
+    };
+
+    let metadata = CampaignMetadata::parse(&text);
+    if metadata.is_empty() {
+        debug!("No campaign metadata found");
+        return None;
+    }
+
+    debug!(metadata = ?metadata, "Found campaign metadata");
+    Some(metadata)
+}
+
+/// Fetch the campaign page HTML
+pub async fn fetch_campaign_page(url: &str) -> Result<String> {
+    reqwest::get(url)
+        .await
+        .context("Failed to fetch webpage")?
+        .text()
+        .await
+        .context("Failed to read response text")
+}
+
+/// Check for new DJs and send alerts
+pub async fn check_for_new_djs(
+    config: &Config,
+    store: &dyn DjStore,
+    campaign: &Campaign,
+    state: Option<&AppState>,
+) -> Result<CheckOutcome> {
+    info!(campaign = %campaign.name, "Checking for new DJs");
+
+    let html = fetch_campaign_page(&campaign.url).await?;
+    let SupportSection {
+        djs: current_djs,
+        stats: parse_stats,
+    } = config.parser.parse(&html, &config.names);
+    let previous_djs = store.load(campaign).await?;
+
+    // Keep the stored metadata in step with the page, falling back to it when the page
+    // yields none
+    let scraped = CampaignMetadata::parse(&html);
+    let stored = store.load_metadata(campaign).await?;
+    let metadata = if scraped.is_empty() {
+        stored.unwrap_or_else(|| campaign.metadata.clone())
+    } else {
+        if stored.as_ref() != Some(&scraped) {
+            store.save_metadata(campaign, &scraped).await?;
+            info!(campaign = %campaign.name, "Saved campaign metadata");
+        }
+        scraped
+    };
+    let campaign = &Campaign {
+        metadata,
+        ..campaign.clone()
+    };
+
+    let issues = health::assess(&parse_stats, current_djs.len(), previous_djs.len());
+    if !issues.is_empty() {
+        warn!(
+            campaign = %campaign.name,
+            stats = ?parse_stats,
+            "⚠️ Suspect check - page layout may have changed, keeping stored DJ list"
+        );
+        for issue in &issues {
+            warn!(campaign = %campaign.name, "  • {}", issue);
+        }
+
+        // Only alert when the campaign turns suspect, not on every check while it stays so;
+        // stored, so one-off runs from cron don't alert every time either
+        let was_suspect = !store.load_scrape_issues(campaign).await?.is_empty();
+        if !was_suspect {
+            config.notifiers.notify_operator(campaign, &issues).await;
+        }
+        let descriptions: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+        store.save_scrape_issues(campaign, &descriptions).await?;
+
+        if let Some(state) = state {
+            update_campaign_stats(state, campaign, previous_djs.len(), &issues).await?;
+        }
+
+        return Ok(CheckOutcome::without_changes(
+            CheckStatus::Suspect,
+            Some(previous_djs.len()),
+        ));
+    }
+
+    if !store.load_scrape_issues(campaign).await?.is_empty() {
+        store.save_scrape_issues(campaign, &[]).await?;
+        info!(campaign = %campaign.name, "Page parses normally again");
+    }
+
+    if previous_djs.is_empty() {
+        info!(
+            campaign = %campaign.name,
+            count = current_djs.len(),
+            "Initial run - found DJs"
+        );
+        debug!(djs = ?current_djs, "Current DJs");
+        store.save(campaign, &current_djs).await?;
+        info!(campaign = %campaign.name, "Saved initial DJ list");
+
+        // Update campaign stats
+        if let Some(state) = state {
+            update_campaign_stats(state, campaign, current_djs.len(), &[]).await?;
+        }
+
+        Ok(CheckOutcome::without_changes(
+            CheckStatus::Initial,
+            Some(current_djs.len()),
+        ))
+    } else {
+        let events = diff_supports(&previous_djs, &current_djs, &config.names);
+        let count = |pred: fn(&SupportEvent) -> bool| events.iter().filter(|e| pred(e)).count();
+        let new_count = count(|e| e.kind == EventKind::New);
+        let updated_count = count(|e| e.kind.is_update());
+        let removed_count = count(|e| e.kind == EventKind::Removed);
+
+        if new_count > 0 {
+            info!(
+                campaign = %campaign.name,
+                count = new_count,
+                "🚨 ALERT: New DJ support detected!"
+            );
+        }
+        if updated_count > 0 {
+            info!(
+                campaign = %campaign.name,
+                count = updated_count,
+                "💬 DJ comments/ratings updated"
+            );
+        }
+        if removed_count > 0 {
+            warn!(
+                campaign = %campaign.name,
+                count = removed_count,
+                "⚠️ DJ support removed!"
+            );
+        }
+        for event in &events {
+            match event.kind {
+                EventKind::New => info!("✨ {}", format_event_line(event)),
+                EventKind::Removed => info!("❌ {} (removed)", format_event_line(event)),
+                _ => info!("💬 {}", format_event_line(event)),
+            }
+        }
+
+        let rules = match &campaign.settings.alert_rules {
+            Some(overrides) => config.alert_rules.with_overrides(overrides),
+            None => config.alert_rules.clone(),
+        };
+        let (alert_events, filtered): (Vec<SupportEvent>, Vec<SupportEvent>) = events
+            .iter()
+            .filter(|e| e.kind != EventKind::Removed || config.alert_on_removals)
+            .cloned()
+            .partition(|e| rules.matches(e, &config.names));
+        if !filtered.is_empty() {
+            info!(
+                campaign = %campaign.name,
+                count = filtered.len(),
+                "Not alerting on changes that don't match the alert rules"
+            );
+        }
+
+        if !alert_events.is_empty() {
+            // Stored before the DJ list is saved, so a failed send is retried rather than lost
+            match &config.digest {
+                Some(digest) if !campaign.settings.immediate_alerts => {
+                    let period = digest.period(Utc::now());
+                    info!(
+                        campaign = %campaign.name,
+                        count = alert_events.len(),
+                        at = %period.until,
+                        "Held changes for the next digest"
+                    );
+                    let deliveries = plan_digest(config, campaign, &alert_events, &period);
+                    config
+                        .outbox
+                        .enqueue_digest(store, campaign, &deliveries, period)
+                        .await?;
+                }
+                _ => {
+                    let deliveries = plan_deliveries(config, campaign, &rules, &alert_events);
+                    config.outbox.enqueue(store, campaign, &deliveries).await?;
+                }
+            }
+        }
+
+        if events.is_empty() {
+            info!(
+                campaign = %campaign.name,
+                total = current_djs.len(),
+                "No new DJs found"
+            );
+
+            // Debug: Show a few examples of what we're tracking
+            if !current_djs.is_empty() {
+                debug!("Sample of tracked DJs:");
+                for (i, dj) in current_djs.iter().take(5).enumerate() {
+                    debug!("{}. {}", i + 1, format_dj_line(dj));
+                }
+            }
+        }
+
+        store.save(campaign, &current_djs).await?;
+        store.record_events(campaign, &events).await?;
+        if !alert_events.is_empty() {
+            config.outbox.wake();
+        }
+
+        // Update campaign stats
+        if let Some(state) = state {
+            update_campaign_stats(state, campaign, current_djs.len(), &[]).await?;
+        }
+
+        Ok(CheckOutcome {
+            status: match events.is_empty() {
+                true => CheckStatus::Unchanged,
+                false => CheckStatus::Changed,
+            },
+            djs: Some(current_djs.len()),
+            new: new_count,
+            updated: updated_count,
+            removed: removed_count,
+            alerts: alert_events.len(),
+        })
+    }
+}
+
+/// Build the configured store and fill in each campaign's metadata from its page,
+/// falling back to what was stored
+pub async fn prepare(config: &mut Config) -> Result<Arc<dyn DjStore>> {
+    debug!(
+        campaigns = config.campaigns.len(),
+        "Fetching track information"
+    );
+
+    let store = storage::build_store(
+        &config.storage,
+        &config.blob_name_prefix,
+        &config.retention,
+        &config.names,
+    )?;
+
+    info!("Storage configured");
+
+    // Fetch track information for all campaigns, falling back to what was stored
+    info!("Fetching track information");
+    for campaign in &mut config.campaigns {
+        debug!(url = %campaign.url, "Fetching metadata");
+        if let Some(metadata) = fetch_metadata(&campaign.url).await {
+            campaign.metadata = metadata;
+        } else {
+            match store.load_metadata(campaign).await {
+                Ok(Some(metadata)) => campaign.metadata = metadata,
+                Ok(None) => {}
+                Err(e) => {
+                    warn!(campaign = %campaign.name, error = %e, "Failed to load stored metadata")
+                }
+            }
+        }
+    }
+
+    debug!("Track information fetched");
+
+    info!("Campaigns:");
+    for campaign in &config.campaigns {
+        info!("  • {} ({})", campaign.display_name(), campaign.url);
+    }
+
+    Ok(store)
+}
+
+/// Check every campaign once; a failing campaign doesn't stop the others
+pub async fn check_all(
+    config: &Config,
+    store: &dyn DjStore,
+    state: Option<&AppState>,
+) -> Vec<CampaignSummary> {
+    let mut summaries = Vec::new();
+    for campaign in &config.campaigns {
+        debug!(campaign = %campaign.name, "Checking campaign");
+        let summary = match check_for_new_djs(config, store, campaign, state).await {
+            Ok(outcome) => CampaignSummary {
+                campaign: campaign.name.clone(),
+                outcome,
+                error: None,
+            },
+            Err(e) => {
+                error!(campaign = %campaign.name, error = %e, "Error during check");
+                CampaignSummary {
+                    campaign: campaign.name.clone(),
+                    outcome: CheckOutcome::without_changes(CheckStatus::Failed, None),
+                    error: Some(format!("{:#}", e)),
+                }
+            }
+        };
+        if let Err(e) = store.record_check(&summary).await {
+            warn!(campaign = %campaign.name, error = %e, "Failed to record check");
+        }
+        summaries.push(summary);
+    }
+    summaries
+}
+
+/// Route a campaign's changes to the digest of every destination, holding the digest back
+/// for recipients whose quiet hours it would fall in
+fn plan_digest(
+    config: &Config,
+    campaign: &Campaign,
+    events: &[SupportEvent],
+    period: &DigestPeriod,
+) -> Vec<Delivery> {
+    let due = period.until();
+    config
+        .notifiers
+        .destinations(campaign)
+        .into_iter()
+        .map(|(channel, target)| {
+            let (quiet_hours, timezone) = campaign.settings.quiet_hours_for(
+                target.as_deref(),
+                config.quiet_hours,
+                config.timezone,
+            );
+            Delivery {
+                channel,
+                target,
+                events: events.to_vec(),
+                hold_until: quiet_hours.and_then(|quiet| quiet.held_until(due, timezone)),
+            }
+        })
+        .collect()
+}
+
+/// Split a campaign's alerts by destination, holding back those for recipients in their
+/// quiet hours
+fn plan_deliveries(
+    config: &Config,
+    campaign: &Campaign,
+    rules: &AlertRules,
+    events: &[SupportEvent],
+) -> Vec<Delivery> {
+    let now = Utc::now();
+    let mut deliveries = Vec::new();
+    for (channel, target) in config.notifiers.destinations(campaign) {
+        let (quiet_hours, timezone) = campaign.settings.quiet_hours_for(
+            target.as_deref(),
+            config.quiet_hours,
+            config.timezone,
+        );
+        let hold_until = quiet_hours.and_then(|quiet| quiet.held_until(now, timezone));
+        // VIP DJs break through quiet hours
+        let (vip, held): (Vec<SupportEvent>, Vec<SupportEvent>) = events
+            .iter()
+            .cloned()
+            .partition(|e| hold_until.is_none() || rules.is_vip(e, &config.names));
+        if let Some(until) = hold_until
+            && !held.is_empty()
+        {
+            info!(
+                campaign = %campaign.name,
+                notifier = %channel,
+                target = ?target,
+                count = held.len(),
+                until = %until.with_timezone(&timezone).to_rfc3339(),
+                "Quiet hours, holding alerts"
+            );
+        }
+        deliveries.push(Delivery {
+            channel,
+            target: target.clone(),
+            events: vip,
+            hold_until: None,
+        });
+        deliveries.push(Delivery {
+            channel,
+            target,
+            events: held,
+            hold_until,
+        });
+    }
+    deliveries
+}
+
+/// Run the monitor: deliver alerts and digests and check every campaign at the
+/// configured interval, forever
+///
+/// With `serve_http`, the status API is served on the configured port as well.
+pub async fn run(mut config: Config, serve_http: bool) -> Result<()> {
+    let store = prepare(&mut config).await?;
+
+    debug!("Creating application state");
+
+    // Create shared application state
+    let app_state = AppState {
+        config: Arc::new(config.clone()),
+        store: store.clone(),
+        campaign_stats: Arc::new(RwLock::new(Vec::new())),
+    };
+
+    if serve_http {
+        debug!(port = config.http_port, "Starting HTTP server");
+
+        // Start HTTP server in background
+        let http_port = config.http_port;
+        let server_state = app_state.clone();
+        tokio::spawn(async move {
+            start_http_server(server_state, http_port).await;
+        });
+
+        debug!("HTTP server spawned");
+    }
+
+    let outbox = config.outbox.clone();
+    let outbox_store = store.clone();
+    let notifiers = config.notifiers.clone();
+    let campaigns = config.campaigns.clone();
+    tokio::spawn(async move {
+        outbox.run(outbox_store, notifiers, campaigns).await;
+    });
+
+    if serve_http {
+        // Give the server a moment to start
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    debug!(campaigns = config.campaigns.len(), "Running initial checks");
+
+    // Run initial check for all campaigns
+    check_all(&config, store.as_ref(), Some(&app_state)).await;
+
+    debug!("Initial checks complete, starting periodic loop");
+
+    // Set up periodic checks
+    let mut interval = time::interval(Duration::from_secs(config.check_interval_minutes * 60));
+    interval.tick().await; // First tick completes immediately
+
+    info!("Entering main monitoring loop");
+
+    loop {
+        interval.tick().await;
+        debug!("Running periodic check");
+        check_all(&config, store.as_ref(), Some(&app_state)).await;
+    }
+}
+
+/// Check every campaign once, deliver the alerts that are due and return what happened
+///
+/// Alerts held for quiet hours or a digest, or waiting for a retry, stay in the outbox for
+/// a later run.
+/// Failed checks and deliveries, and an outbox that can't be loaded or saved, are reported
+/// in the summary rather than as an error.
+pub async fn run_once(mut config: Config) -> Result<RunSummary> {
+    let started_at = Utc::now();
+    let store = prepare(&mut config).await?;
+    let campaigns = check_all(&config, store.as_ref(), None).await;
+    let outbox = match config
+        .outbox
+        .flush(store.as_ref(), &config.notifiers, &config.campaigns)
+        .await
+    {
+        Ok(report) => report,
+        Err(e) => {
+            error!(error = %e, "Failed to deliver alerts from the outbox");
+            DeliveryReport {
+                error: Some(format!("{:#}", e)),
+                ..DeliveryReport::default()
+            }
+        }
+    };
+
+    Ok(RunSummary {
+        started_at: started_at.to_rfc3339(),
+        finished_at: Utc::now().to_rfc3339(),
+        campaigns,
+        outbox,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::{Notifier, Notifiers};
+    use crate::outbox::Outbox;
+    use crate::quiet::QuietHours;
+    use crate::storage::{RetentionPolicy, StorageConfig};
+    use crate::testing::{campaign, new_support};
+    use async_trait::async_trait;
+    use chrono::{DateTime, TimeZone};
+
+    struct Slack;
+
+    #[async_trait]
+    impl Notifier for Slack {
+        fn name(&self) -> &'static str {
+            "slack"
+        }
+
+        fn describe(&self) -> String {
+            "test channel".to_string()
+        }
+
+        async fn notify(&self, _campaign: &Campaign, _events: &[SupportEvent]) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn config(quiet_hours: Option<QuietHours>) -> Config {
+        let mut notifiers = Notifiers::default();
+        notifiers.push(Arc::new(Slack));
+        Config {
+            campaigns: Vec::new(),
+            storage: StorageConfig::Local { dir: "data".into() },
+            blob_name_prefix: "dj_list".to_string(),
+            retention: RetentionPolicy::default(),
+            names: Default::default(),
+            parser: Default::default(),
+            notifiers,
+            outbox: Arc::new(Outbox::new(10, Duration::from_secs(60))),
+            digest: None,
+            alert_on_removals: false,
+            alert_rules: AlertRules::default(),
+            quiet_hours,
+            timezone: chrono_tz::Tz::UTC,
+            check_interval_minutes: 60,
+            http_port: 8080,
+        }
+    }
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 15, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn digests_due_in_quiet_hours_are_held() {
+        let period = |until: DateTime<Utc>| DigestPeriod {
+            since: (until - chrono::Duration::hours(1)).to_rfc3339(),
+            until: until.to_rfc3339(),
+        };
+        let events = [new_support("Dixon")];
+        let night = config(Some("22:00-08:00".parse().unwrap()));
+
+        let held = plan_digest(&night, &campaign("pmqtne"), &events, &period(at(23)));
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].events.len(), 1);
+        assert_eq!(
+            held[0].hold_until,
+            Some(Utc.with_ymd_and_hms(2025, 1, 16, 8, 0, 0).unwrap())
+        );
+
+        let due = plan_digest(&night, &campaign("pmqtne"), &events, &period(at(12)));
+        assert_eq!(due[0].hold_until, None);
+
+        let mut off = campaign("xk2v9a");
+        off.settings.quiet_hours = Some("off".parse().unwrap());
+        let due = plan_digest(&night, &off, &events, &period(at(23)));
+        assert_eq!(due[0].hold_until, None);
+    }
+
+    #[test]
+    fn failed_checks_leave_out_the_dj_count() {
+        let summary = RunSummary {
+            started_at: "2025-01-15T10:30:00+00:00".to_string(),
+            finished_at: "2025-01-15T10:30:01+00:00".to_string(),
+            campaigns: vec![
+                CampaignSummary {
+                    campaign: "pmqtne".to_string(),
+                    outcome: CheckOutcome::without_changes(CheckStatus::Unchanged, Some(0)),
+                    error: None,
+                },
+                CampaignSummary {
+                    campaign: "xk2v9a".to_string(),
+                    outcome: CheckOutcome::without_changes(CheckStatus::Failed, None),
+                    error: Some("Failed to fetch webpage".to_string()),
+                },
+            ],
+            outbox: DeliveryReport::default(),
+        };
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["campaigns"][0]["djs"], 0);
+        assert!(json["campaigns"][1].get("djs").is_none());
+        assert_eq!(json["campaigns"][1]["status"], "failed");
+        assert_eq!(summary.exit_code(), 2);
+    }
+}
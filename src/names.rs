@@ -0,0 +1,248 @@
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::DjSupport;
+
+/// Prefixes ignored when matching DJ names ("DJ Foo" matches "Foo")
+const KNOWN_PREFIXES: &[&str] = &["dj", "d.j.", "dj."];
+
+/// Cleans up scraped DJ names and merges spelling variants into one canonical DJ
+///
+/// Display names are NFKC-folded, stripped of emoji and trailing `(Label)` / `[Label]`
+/// suffixes, and whitespace-collapsed. Matching additionally ignores case, accents,
+/// punctuation and known prefixes such as "DJ". User-maintained aliases map any variant
+/// to a canonical name that is used across all campaigns.
+#[derive(Debug, Clone, Default)]
+pub struct NameNormalizer {
+    /// Identity key of every alias (and canonical name) -> canonical display name
+    aliases: HashMap<String, String>,
+}
+
+impl NameNormalizer {
+    /// Load aliases from a JSON file mapping canonical names to their variants:
+    ///
+    /// ```json
+    /// { "Charlotte de Witte": ["CDW", "Charlotte De Witte (KNTXT)"] }
+    /// ```
+    pub fn from_alias_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read alias file: {}", path.display()))?;
+        let groups: HashMap<String, Vec<String>> = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse alias file: {}", path.display()))?;
+
+        let mut aliases = HashMap::new();
+        for (canonical, variants) in groups {
+            let canonical = clean_display_name(&canonical);
+            for variant in variants.iter().chain(std::iter::once(&canonical)) {
+                let key = identity_key(variant);
+                if let Some(existing) = aliases.insert(key, canonical.clone())
+                    && existing != canonical
+                {
+                    anyhow::bail!(
+                        "Alias '{}' is listed under both '{}' and '{}'",
+                        variant,
+                        existing,
+                        canonical
+                    );
+                }
+            }
+        }
+
+        Ok(NameNormalizer { aliases })
+    }
+
+    /// Number of known aliases (including canonical names)
+    pub fn alias_count(&self) -> usize {
+        self.aliases.len()
+    }
+
+    /// The name a scraped DJ should be stored under
+    pub fn canonical(&self, raw: &str) -> String {
+        let display = clean_display_name(raw);
+        self.aliases
+            .get(&identity_key(&display))
+            .cloned()
+            .unwrap_or(display)
+    }
+
+    /// Key under which two names are considered the same DJ
+    pub fn identity(&self, name: &str) -> String {
+        identity_key(&self.canonical(name))
+    }
+
+    /// Canonicalize every DJ name and merge entries that refer to the same DJ,
+    /// keeping the one with the most detail (comment and/or stars)
+    pub fn merge(&self, djs: HashSet<DjSupport>) -> HashSet<DjSupport> {
+        let mut merged: HashMap<String, DjSupport> = HashMap::new();
+
+        for mut dj in djs {
+            dj.name = self.canonical(&dj.name);
+            if dj.name.is_empty() {
+                continue;
+            }
+
+            let key = identity_key(&dj.name);
+            match merged.get(&key) {
+                Some(existing) if detail(existing) >= detail(&dj) => {}
+                _ => {
+                    merged.insert(key, dj);
+                }
+            }
+        }
+
+        merged.into_values().collect()
+    }
+}
+
+/// How much information a listing carries, used to pick between duplicates
+fn detail(dj: &DjSupport) -> (u8, &str) {
+    (
+        dj.comment.is_some() as u8 + dj.stars.is_some() as u8,
+        &dj.name,
+    )
+}
+
+/// Whether a character is an emoji or pictograph (including star ratings and modifiers)
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF   // Emoji, pictographs, flags, symbols
+        | 0x2600..=0x27BF   // Misc symbols and dingbats
+        | 0x2B00..=0x2BFF   // Arrows and stars (⭐)
+        | 0x2300..=0x23FF   // Misc technical (⌚, ⏰)
+        | 0xFE00..=0xFE0F   // Variation selectors
+        | 0x200D            // Zero-width joiner
+        | 0xE0020..=0xE007F // Tag characters
+    )
+}
+
+/// Clean a scraped name for display: NFKC, no emoji, no trailing `(Label)`, single spaces
+fn clean_display_name(raw: &str) -> String {
+    let folded: String = raw.nfkc().filter(|c| !is_emoji(*c)).collect();
+    let mut name = folded.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    // Strip trailing "(Label)" / "[Label]" suffixes, as long as something remains
+    loop {
+        let trimmed = name.trim_end_matches([' ', '-', '–', '|', '•', '·', ':']);
+        let stripped = [('(', ')'), ('[', ']')].iter().find_map(|(open, close)| {
+            let inner = trimmed.strip_suffix(*close)?;
+            let start = inner.rfind(*open)?;
+            let rest = inner[..start].trim_end();
+            (!rest.is_empty()).then(|| rest.to_string())
+        });
+        match stripped {
+            Some(rest) => name = rest,
+            None => {
+                name = trimmed.to_string();
+                break;
+            }
+        }
+    }
+
+    name.trim_start_matches([' ', '-', '–', '|', '•', '·', ':'])
+        .to_string()
+}
+
+/// Matching key: lowercase, no accents or punctuation, known prefixes removed
+fn identity_key(name: &str) -> String {
+    let folded: String = clean_display_name(name)
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .map(|c| if c == '-' || c == '_' { ' ' } else { c })
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace() || *c == '.')
+        .collect();
+    let mut words: Vec<&str> = folded.split_whitespace().collect();
+
+    if words.len() > 1 && KNOWN_PREFIXES.contains(&words[0]) {
+        words.remove(0);
+    }
+
+    words.join(" ").chars().filter(|c| *c != '.').collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::dj;
+
+    fn alias_file(json: &str) -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), json).unwrap();
+        file
+    }
+
+    #[test]
+    fn display_names() {
+        // NFKC folds full-width letters and ligatures
+        assert_eq!(clean_display_name("ＤＪ Ｔｅｎｎｉｓ"), "DJ Tennis");
+        assert_eq!(clean_display_name("ﬁsher"), "fisher");
+        // Emoji, ratings and extra whitespace go
+        assert_eq!(clean_display_name("  Kölsch 🔥⭐⭐ "), "Kölsch");
+        assert_eq!(clean_display_name("Maceo\tPlex"), "Maceo Plex");
+        // Trailing labels go, but never the whole name
+        assert_eq!(clean_display_name("Dixon (Innervisions)"), "Dixon");
+        assert_eq!(clean_display_name("Dixon [Innervisions] - "), "Dixon");
+        assert_eq!(
+            clean_display_name("Adam Beyer (Drumcode) (Live)"),
+            "Adam Beyer"
+        );
+        assert_eq!(clean_display_name("(Unknown)"), "(Unknown)");
+        assert_eq!(clean_display_name("| Âme"), "Âme");
+    }
+
+    #[test]
+    fn identity_keys() {
+        assert_eq!(identity_key("Âme"), "ame");
+        assert_eq!(identity_key("Solomun!"), "solomun");
+        assert_eq!(identity_key("Tale-Of-Us"), "tale of us");
+        assert_eq!(
+            identity_key("Charlotte de Witte (KNTXT)"),
+            "charlotte de witte"
+        );
+        // Known prefixes are dropped, but not when they are the whole name
+        assert_eq!(identity_key("DJ Tennis"), "tennis");
+        assert_eq!(identity_key("d.j. Tennis"), "tennis");
+        assert_eq!(identity_key("DJ. Tennis"), "tennis");
+        assert_eq!(identity_key("DJ"), "dj");
+        assert_eq!(identity_key("Djebali"), "djebali");
+    }
+
+    #[test]
+    fn aliases() {
+        let file = alias_file(r#"{ "Charlotte de Witte": ["CDW", "Charlotte De Witte (KNTXT)"] }"#);
+        let names = NameNormalizer::from_alias_file(file.path()).unwrap();
+        assert_eq!(names.alias_count(), 2);
+        assert_eq!(names.canonical("cdw"), "Charlotte de Witte");
+        assert_eq!(
+            names.canonical("CHARLOTTE DE WITTE 🔥"),
+            "Charlotte de Witte"
+        );
+        assert_eq!(names.canonical("Amelie Lens"), "Amelie Lens");
+        assert_eq!(names.identity("CDW"), names.identity("Charlotte de Witte"));
+
+        let merged = names.merge(HashSet::from([
+            dj("CDW", None, None),
+            dj("Charlotte de Witte", Some("Banger"), Some(5)),
+        ]));
+        assert_eq!(merged.len(), 1);
+        assert_eq!(
+            merged.into_iter().next().unwrap().comment.as_deref(),
+            Some("Banger")
+        );
+    }
+
+    #[test]
+    fn conflicting_aliases() {
+        let file = alias_file(r#"{ "Âme": ["Ame"], "Dixon": ["ame"] }"#);
+        let error = NameNormalizer::from_alias_file(file.path()).unwrap_err();
+        assert!(
+            error.to_string().contains("is listed under both"),
+            "{}",
+            error
+        );
+    }
+}
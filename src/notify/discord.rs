@@ -0,0 +1,232 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::env;
+use tracing::debug;
+
+use super::{Notifier, describe_previous, event_marker, summarize, truncate};
+use crate::Campaign;
+use crate::diff::{EventKind, SupportEvent};
+
+/// Discord allows 10 embeds per message
+const MAX_EMBEDS_PER_MESSAGE: usize = 10;
+
+/// Discord rejects messages whose embeds hold more than 6000 characters in total
+const MAX_EMBED_CHARS_PER_MESSAGE: usize = 6000;
+
+/// Posts support changes to Discord webhooks, one embed per DJ
+///
+/// Each campaign posts to its own `discord_webhook_url` from the campaign settings, falling
+/// back to `DISCORD_WEBHOOK_URL`. Campaigns with neither are skipped.
+pub struct DiscordNotifier {
+    client: reqwest::Client,
+    default_webhook_url: Option<String>,
+}
+
+impl DiscordNotifier {
+    /// Enabled by `DISCORD_WEBHOOK_URL` or by any campaign with its own webhook
+    pub fn from_env(campaigns: &[Campaign]) -> Option<Self> {
+        let default_webhook_url = env::var("DISCORD_WEBHOOK_URL").ok();
+        let any_campaign_webhook = campaigns
+            .iter()
+            .any(|c| c.settings.discord_webhook_url.is_some());
+
+        (default_webhook_url.is_some() || any_campaign_webhook).then(|| DiscordNotifier {
+            client: reqwest::Client::new(),
+            default_webhook_url,
+        })
+    }
+
+    fn webhook_url<'a>(&'a self, campaign: &'a Campaign) -> Option<&'a str> {
+        campaign
+            .settings
+            .discord_webhook_url
+            .as_deref()
+            .or(self.default_webhook_url.as_deref())
+    }
+}
+
+/// Sidebar colour of an embed: green for new support, red for removals, blue for updates
+fn embed_color(kind: EventKind) -> u32 {
+    match kind {
+        EventKind::New => 0x2ecc71,
+        EventKind::Removed => 0xe74c3c,
+        _ => 0x3498db,
+    }
+}
+
+/// An embed for one event, along with the number of characters Discord counts against
+/// the per-message limit
+fn event_embed(campaign: &Campaign, event: &SupportEvent) -> (Value, usize) {
+    let dj = &event.dj;
+    let title = truncate(&format!("{} {}", event_marker(event.kind), dj.name), 256);
+    let mut chars = title.chars().count();
+
+    let mut embed = json!({
+        "title": title,
+        "url": campaign.url,
+        "color": embed_color(event.kind),
+    });
+    if let Some(comment) = &dj.comment {
+        let description = truncate(comment, 4096);
+        chars += description.chars().count();
+        embed["description"] = json!(description);
+    }
+
+    let mut fields = Vec::new();
+    if let Some(stars) = dj.stars {
+        let value = "⭐".repeat(stars as usize);
+        chars += "Stars".len() + value.chars().count();
+        fields.push(json!({ "name": "Stars", "value": value, "inline": true }));
+    }
+    if let Some(note) = describe_previous(event) {
+        let value = truncate(&note, 1024);
+        chars += "Change".len() + value.chars().count();
+        fields.push(json!({ "name": "Change", "value": value, "inline": true }));
+    }
+    if !fields.is_empty() {
+        embed["fields"] = json!(fields);
+    }
+
+    if let Some(artwork_url) = &campaign.metadata.artwork_url {
+        embed["thumbnail"] = json!({ "url": artwork_url });
+    }
+
+    (embed, chars)
+}
+
+/// Group embeds into messages that stay within Discord's count and size limits
+fn batch_embeds(embeds: Vec<(Value, usize)>) -> Vec<Vec<Value>> {
+    let mut batches: Vec<Vec<Value>> = Vec::new();
+    let mut batch = Vec::new();
+    let mut batch_chars = 0;
+
+    for (embed, chars) in embeds {
+        if !batch.is_empty()
+            && (batch.len() == MAX_EMBEDS_PER_MESSAGE
+                || batch_chars + chars > MAX_EMBED_CHARS_PER_MESSAGE)
+        {
+            batches.push(std::mem::take(&mut batch));
+            batch_chars = 0;
+        }
+        batch.push(embed);
+        batch_chars += chars;
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    fn describe(&self) -> String {
+        match self.default_webhook_url {
+            Some(_) => "Discord (default webhook + per-campaign webhooks)".to_string(),
+            None => "Discord (per-campaign webhooks only)".to_string(),
+        }
+    }
+
+    async fn notify(&self, campaign: &Campaign, events: &[SupportEvent]) -> Result<()> {
+        let Some(webhook_url) = self.webhook_url(campaign) else {
+            debug!(campaign = %campaign.name, "No Discord webhook for campaign, skipping");
+            return Ok(());
+        };
+
+        let content = truncate(
+            &format!(
+                "🎵 **{}**: {}\n<{}>",
+                campaign.display_name(),
+                summarize(events),
+                campaign.url
+            ),
+            2000,
+        );
+        let embeds = events.iter().map(|e| event_embed(campaign, e)).collect();
+
+        for (index, batch) in batch_embeds(embeds).into_iter().enumerate() {
+            let mut message = json!({ "embeds": batch });
+            // The summary heads the first message only
+            if index == 0 {
+                message["content"] = json!(content);
+            }
+
+            let response = super::send(
+                self.client.post(webhook_url).json(&message),
+                "Failed to post to Discord",
+            )
+            .await?;
+
+            if !response.status().is_success() {
+                let error_text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string());
+                anyhow::bail!("Discord webhook error: {}", error_text);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Embeds of the given sizes, each tagged with its position
+    fn embeds(sizes: &[usize]) -> Vec<(Value, usize)> {
+        sizes
+            .iter()
+            .enumerate()
+            .map(|(i, &chars)| (json!({ "title": i }), chars))
+            .collect()
+    }
+
+    fn batch_sizes(batches: &[Vec<Value>]) -> Vec<usize> {
+        batches.iter().map(Vec::len).collect()
+    }
+
+    #[test]
+    fn batches_hold_at_most_ten_embeds() {
+        assert_eq!(batch_sizes(&batch_embeds(embeds(&[10; 10]))), [10]);
+        assert_eq!(batch_sizes(&batch_embeds(embeds(&[10; 11]))), [10, 1]);
+        assert_eq!(batch_sizes(&batch_embeds(embeds(&[10; 25]))), [10, 10, 5]);
+        assert!(batch_embeds(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn batches_hold_at_most_6000_characters() {
+        // Exactly at the limit still fits in one message
+        assert_eq!(batch_sizes(&batch_embeds(embeds(&[3000, 3000]))), [2]);
+        assert_eq!(batch_sizes(&batch_embeds(embeds(&[3000, 3001]))), [1, 1]);
+
+        let batches = batch_embeds(embeds(&[4000, 1500, 1000, 5000, 900]));
+        assert_eq!(batch_sizes(&batches), [2, 2, 1]);
+        // Embeds keep their order across messages
+        let titles: Vec<&Value> = batches.iter().flatten().map(|e| &e["title"]).collect();
+        assert_eq!(titles, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn embed_characters_are_counted() {
+        use crate::testing::{campaign, dj, event};
+
+        let (embed, chars) = event_embed(
+            &campaign("pmqtne"),
+            &event(
+                EventKind::RatingChanged,
+                dj("Dixon", Some("Huge"), Some(2)),
+                Some(dj("Dixon", Some("Huge"), Some(1))),
+            ),
+        );
+        assert_eq!(embed["title"], "💬 Dixon");
+        assert_eq!(embed["color"], 0x3498db);
+        // Title, description, then the names and values of both fields
+        assert_eq!(chars, 7 + 4 + 5 + 2 + 6 + 11);
+    }
+}
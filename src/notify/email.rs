@@ -0,0 +1,497 @@
+use anyhow::{Context, Result};
+use minijinja::{Environment, context};
+use serde::Serialize;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use tracing::info;
+
+use super::{describe_previous, format_event_line, summarize};
+use crate::diff::{EventKind, SupportEvent};
+use crate::digest::DigestReport;
+use crate::health::HealthIssue;
+use crate::{Campaign, DjSupport};
+
+/// The built-in templates, by name; any of them can be overridden by a file of the same
+/// name in `EMAIL_TEMPLATE_DIR`
+const DEFAULT_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "support_alert_subject.txt",
+        include_str!("templates/support_alert_subject.txt"),
+    ),
+    (
+        "support_alert.html",
+        include_str!("templates/support_alert.html"),
+    ),
+    (
+        "support_alert.txt",
+        include_str!("templates/support_alert.txt"),
+    ),
+    (
+        "digest_subject.txt",
+        include_str!("templates/digest_subject.txt"),
+    ),
+    ("digest.html", include_str!("templates/digest.html")),
+    ("digest.txt", include_str!("templates/digest.txt")),
+    (
+        "operator_alert_subject.txt",
+        include_str!("templates/operator_alert_subject.txt"),
+    ),
+    (
+        "operator_alert.html",
+        include_str!("templates/operator_alert.html"),
+    ),
+    (
+        "operator_alert.txt",
+        include_str!("templates/operator_alert.txt"),
+    ),
+];
+
+/// Who receives email alerts: everyone on `RECIPIENT_EMAIL` gets every campaign, and the
+/// `recipients` of a campaign's settings get that campaign
+#[derive(Debug, Clone)]
+pub struct Recipients {
+    default: Vec<String>,
+}
+
+impl Recipients {
+    /// Read `RECIPIENT_EMAIL` (comma-separated), which may only be left out when campaigns
+    /// list their own recipients
+    pub fn from_env(campaigns: &[Campaign]) -> Result<Self> {
+        let default = env::var("RECIPIENT_EMAIL")
+            .map(|addresses| split_addresses(&addresses))
+            .unwrap_or_default();
+        if default.is_empty() && campaigns.iter().all(|c| c.settings.recipients.is_empty()) {
+            anyhow::bail!("RECIPIENT_EMAIL environment variable not set");
+        }
+        Ok(Recipients { default })
+    }
+
+    /// Everyone receiving email alerts of some campaign, for validation and logging
+    pub fn all<'a>(&'a self, campaigns: &'a [Campaign]) -> impl Iterator<Item = &'a String> {
+        self.default.iter().chain(
+            campaigns
+                .iter()
+                .flat_map(|c| c.settings.recipients.iter().map(|r| &r.to)),
+        )
+    }
+
+    /// The default recipients plus the campaign's own, each address once
+    pub fn for_campaign(&self, campaign: &Campaign) -> Vec<String> {
+        let mut recipients: Vec<String> = Vec::new();
+        let own = campaign.settings.recipients.iter().map(|r| &r.to);
+        for address in self.default.iter().chain(own) {
+            if !recipients.iter().any(|r| r.eq_ignore_ascii_case(address)) {
+                recipients.push(address.clone());
+            }
+        }
+        recipients
+    }
+
+    /// Split a digest into one per recipient, holding only the campaigns they receive
+    pub fn split_digest(&self, report: &DigestReport) -> Vec<(String, DigestReport)> {
+        let mut digests: Vec<(String, DigestReport)> = Vec::new();
+        for entry in &report.entries {
+            for recipient in self.for_campaign(&entry.campaign) {
+                match digests
+                    .iter_mut()
+                    .find(|(r, _)| r.eq_ignore_ascii_case(&recipient))
+                {
+                    Some((_, digest)) => digest.entries.push(entry.clone()),
+                    None => digests.push((
+                        recipient,
+                        DigestReport {
+                            entries: vec![entry.clone()],
+                            ..report.clone_period()
+                        },
+                    )),
+                }
+            }
+        }
+        digests
+    }
+}
+
+impl fmt::Display for Recipients {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.default.is_empty() {
+            true => write!(f, "campaign recipients only"),
+            false => write!(f, "{} + campaign recipients", self.default.join(", ")),
+        }
+    }
+}
+
+/// Split a comma-separated list of email addresses
+pub fn split_addresses(addresses: &str) -> Vec<String> {
+    addresses
+        .split(',')
+        .map(|address| address.trim().to_string())
+        .filter(|address| !address.is_empty())
+        .collect()
+}
+
+/// A rendered email, ready to be handed to any email transport
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Subject, HTML and text templates of the alert emails
+///
+/// Templates use Jinja syntax (rendered with minijinja). Everything interpolated into
+/// the `.html` templates is HTML-escaped; the `.txt` templates are rendered as is.
+#[derive(Debug)]
+pub struct EmailTemplates {
+    env: Environment<'static>,
+}
+
+/// New supports featured at the top of a digest
+const MAX_DIGEST_HIGHLIGHTS: usize = 5;
+
+/// A DJ event as seen by the templates
+#[derive(Clone, Serialize)]
+struct EventView<'a> {
+    kind: EventKind,
+    dj: &'a DjSupport,
+    /// One star symbol per star, empty when unrated
+    stars: String,
+    /// What an update changed, e.g. `was 3 stars`
+    note: Option<String>,
+    /// The whole event as one plain-text line
+    line: String,
+}
+
+/// The events of one kind, as listed in the email
+#[derive(Serialize)]
+struct GroupView<'a> {
+    title: &'static str,
+    class: &'static str,
+    marker: &'static str,
+    events: Vec<EventView<'a>>,
+}
+
+/// A campaign's events split by kind
+struct EventsByKind<'a> {
+    new: Vec<EventView<'a>>,
+    updated: Vec<EventView<'a>>,
+    removed: Vec<EventView<'a>>,
+}
+
+impl<'a> EventsByKind<'a> {
+    fn new(events: &'a [SupportEvent]) -> Self {
+        let of_kind = |pred: fn(&SupportEvent) -> bool| -> Vec<EventView> {
+            events
+                .iter()
+                .filter(|e| pred(e))
+                .map(EventView::new)
+                .collect()
+        };
+        EventsByKind {
+            new: of_kind(|e| e.kind == EventKind::New),
+            updated: of_kind(|e| e.kind.is_update()),
+            removed: of_kind(|e| e.kind == EventKind::Removed),
+        }
+    }
+
+    /// The non-empty kinds, as listed in the email
+    fn groups(&self) -> Vec<GroupView<'a>> {
+        [
+            ("New Support", "dj-list", "✨", &self.new),
+            ("Updated Support", "dj-list updated", "💬", &self.updated),
+            ("Removed Support", "dj-list removed", "❌", &self.removed),
+        ]
+        .into_iter()
+        .filter(|(_, _, _, group)| !group.is_empty())
+        .map(|(title, class, marker, group)| GroupView {
+            title,
+            class,
+            marker,
+            events: group.clone(),
+        })
+        .collect()
+    }
+}
+
+/// One campaign's section of a digest
+#[derive(Serialize)]
+struct TrackView<'a> {
+    title: String,
+    campaign: &'a Campaign,
+    /// e.g. `2 new, 1 updated`
+    summary: String,
+    groups: Vec<GroupView<'a>>,
+}
+
+/// A new support featured at the top of a digest
+#[derive(Serialize)]
+struct HighlightView<'a> {
+    track: String,
+    event: EventView<'a>,
+}
+
+#[derive(Serialize)]
+struct DetailView<'a> {
+    caption: &'static str,
+    value: &'a str,
+}
+
+impl EventView<'_> {
+    fn new(event: &SupportEvent) -> EventView<'_> {
+        EventView {
+            kind: event.kind,
+            dj: &event.dj,
+            stars: "⭐".repeat(event.dj.stars.unwrap_or(0) as usize),
+            note: describe_previous(event),
+            line: format_event_line(event),
+        }
+    }
+}
+
+impl EmailTemplates {
+    /// The built-in templates, overridden by any file of the same name in
+    /// `EMAIL_TEMPLATE_DIR`; fails on templates that don't parse
+    pub fn from_env() -> Result<Self> {
+        let dir = env::var("EMAIL_TEMPLATE_DIR").ok().map(PathBuf::from);
+
+        let mut env = Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+
+        for (name, default) in DEFAULT_TEMPLATES {
+            let path = dir.as_ref().map(|dir| dir.join(name));
+            let source = match path {
+                Some(path) if path.exists() => {
+                    info!(template = name, path = %path.display(), "Using custom email template");
+                    fs::read_to_string(&path).with_context(|| {
+                        format!("Failed to read email template: {}", path.display())
+                    })?
+                }
+                _ => default.to_string(),
+            };
+            env.add_template_owned(*name, source)
+                .with_context(|| format!("Invalid email template {}", name))?;
+        }
+
+        Ok(EmailTemplates { env })
+    }
+
+    fn render(&self, name: &str, ctx: &minijinja::Value) -> Result<String> {
+        self.env
+            .get_template(name)
+            .and_then(|template| template.render(ctx))
+            .with_context(|| format!("Failed to render email template {}", name))
+    }
+
+    /// Render subject, HTML and text of one email; the subject is kept to a single line
+    fn render_message(&self, prefix: &str, ctx: minijinja::Value) -> Result<EmailMessage> {
+        let subject = self.render(&format!("{}_subject.txt", prefix), &ctx)?;
+        Ok(EmailMessage {
+            subject: subject.split_whitespace().collect::<Vec<_>>().join(" "),
+            html: self.render(&format!("{}.html", prefix), &ctx)?,
+            text: self.render(&format!("{}.txt", prefix), &ctx)?,
+        })
+    }
+
+    /// Render the alert for detected support changes
+    pub fn support_alert(
+        &self,
+        campaign: &Campaign,
+        events: &[SupportEvent],
+    ) -> Result<EmailMessage> {
+        let by_kind = EventsByKind::new(events);
+        let new_with_feedback = by_kind
+            .new
+            .iter()
+            .any(|e| e.dj.comment.is_some() || e.dj.stars.is_some());
+        let release_details: Vec<DetailView> = campaign
+            .metadata
+            .release_details()
+            .into_iter()
+            .map(|(caption, value)| DetailView { caption, value })
+            .collect();
+
+        self.render_message(
+            "support_alert",
+            context! {
+                campaign,
+                title => campaign.display_name(),
+                release_details,
+                groups => by_kind.groups(),
+                new => by_kind.new,
+                updated => by_kind.updated,
+                removed => by_kind.removed,
+                new_with_feedback,
+            },
+        )
+    }
+
+    /// Render a digest of the changes across campaigns, one section per track, headed by
+    /// the best-rated and commented new supports
+    pub fn digest(&self, report: &DigestReport) -> Result<EmailMessage> {
+        let mut tracks = Vec::new();
+        let mut highlights = Vec::new();
+        let (mut new, mut updated, mut removed) = (0, 0, 0);
+
+        for entry in &report.entries {
+            let by_kind = EventsByKind::new(&entry.events);
+            new += by_kind.new.len();
+            updated += by_kind.updated.len();
+            removed += by_kind.removed.len();
+
+            let title = entry.campaign.display_name();
+            highlights.extend(
+                by_kind
+                    .new
+                    .iter()
+                    .filter(|e| e.dj.comment.is_some() || e.dj.stars.is_some())
+                    .map(|event| HighlightView {
+                        track: title.clone(),
+                        event: event.clone(),
+                    }),
+            );
+            tracks.push(TrackView {
+                groups: by_kind.groups(),
+                summary: summarize(&entry.events),
+                campaign: &entry.campaign,
+                title,
+            });
+        }
+
+        // Best-rated first, commented before uncommented
+        highlights.sort_by_key(|h| {
+            std::cmp::Reverse((h.event.dj.stars.unwrap_or(0), h.event.dj.comment.is_some()))
+        });
+        highlights.truncate(MAX_DIGEST_HIGHLIGHTS);
+
+        let format_time =
+            |time: &chrono::DateTime<chrono::Utc>| time.format("%-d %b %Y %H:%M UTC").to_string();
+        self.render_message(
+            "digest",
+            context! {
+                since => format_time(&report.since),
+                until => format_time(&report.until),
+                totals => context! { new, updated, removed },
+                highlights,
+                tracks,
+            },
+        )
+    }
+
+    /// Render the alert telling the operator that a campaign page no longer parses as
+    /// expected
+    pub fn operator_alert(
+        &self,
+        campaign: &Campaign,
+        issues: &[HealthIssue],
+    ) -> Result<EmailMessage> {
+        self.render_message(
+            "operator_alert",
+            context! {
+                campaign,
+                title => campaign.display_name(),
+                issues => issues.iter().map(|i| i.to_string()).collect::<Vec<_>>(),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{dj, event};
+
+    fn campaign(name: &str, artist: &str, title: &str) -> Campaign {
+        let mut campaign = crate::testing::campaign(name);
+        campaign.metadata.artist = Some(artist.to_string());
+        campaign.metadata.title = Some(title.to_string());
+        campaign
+    }
+
+    /// The default templates, with DJ text that must come out HTML-escaped
+    #[test]
+    fn default_support_alert() {
+        let mut campaign = campaign("pmqtne", "Vela", "Afterglow (Remixes)");
+        campaign.metadata.label = Some("Nightshift & Co".to_string());
+        let events = [
+            event(
+                EventKind::New,
+                dj("Dixon", Some("<b>Huge</b> on the floor & beyond"), Some(5)),
+                None,
+            ),
+            event(EventKind::New, dj("Kölsch", None, None), None),
+            event(
+                EventKind::RatingChanged,
+                dj("Maceo Plex", None, Some(4)),
+                Some(dj("Maceo Plex", None, Some(3))),
+            ),
+        ];
+
+        let message = EmailTemplates::from_env()
+            .unwrap()
+            .support_alert(&campaign, &events)
+            .unwrap();
+
+        insta::with_settings!({
+            snapshot_path => "../../tests/snapshots",
+            prepend_module_to_snapshot => false,
+        }, {
+            insta::assert_snapshot!("support_alert_subject", message.subject);
+            insta::assert_snapshot!("support_alert_text", message.text);
+            insta::assert_snapshot!("support_alert_html", message.html);
+        });
+    }
+
+    #[test]
+    fn default_digest() {
+        use crate::digest::{DigestEntry, DigestReport};
+        use chrono::TimeZone;
+
+        let report = DigestReport {
+            since: chrono::Utc.with_ymd_and_hms(2025, 1, 14, 9, 0, 0).unwrap(),
+            until: chrono::Utc.with_ymd_and_hms(2025, 1, 15, 9, 0, 0).unwrap(),
+            entries: vec![
+                DigestEntry {
+                    campaign: campaign("pmqtne", "Vela", "Afterglow (Remixes)"),
+                    events: vec![
+                        event(EventKind::New, dj("Kölsch", None, None), None),
+                        event(
+                            EventKind::New,
+                            dj("Dixon", Some("Huge on the floor"), Some(5)),
+                            None,
+                        ),
+                        event(
+                            EventKind::Removed,
+                            dj("Maceo Plex", Some("Nice"), None),
+                            None,
+                        ),
+                    ],
+                },
+                DigestEntry {
+                    campaign: campaign("xk2pd", "Orbit", "Low Sun"),
+                    events: vec![
+                        event(EventKind::New, dj("Miss Kittin", None, Some(3)), None),
+                        event(
+                            EventKind::CommentAdded,
+                            dj("DJ Tennis", Some("Playing it tonight"), None),
+                            Some(dj("DJ Tennis", None, None)),
+                        ),
+                    ],
+                },
+            ],
+        };
+
+        let message = EmailTemplates::from_env().unwrap().digest(&report).unwrap();
+
+        insta::with_settings!({
+            snapshot_path => "../../tests/snapshots",
+            prepend_module_to_snapshot => false,
+        }, {
+            insta::assert_snapshot!("digest_subject", message.subject);
+            insta::assert_snapshot!("digest_text", message.text);
+            insta::assert_snapshot!("digest_html", message.html);
+        });
+    }
+}
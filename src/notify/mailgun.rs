@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::env;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::Notifier;
+use super::email::{EmailMessage, EmailTemplates, Recipients, split_addresses};
+use crate::Campaign;
+use crate::diff::SupportEvent;
+use crate::digest::DigestReport;
+use crate::health::HealthIssue;
+
+/// Sends alert emails through the Mailgun HTTP API
+///
+/// Enabled by `MAILGUN_API_KEY`; `MAILGUN_DOMAIN` and `RECIPIENT_EMAIL` (or campaign
+/// recipients) are then required.
+pub struct MailgunNotifier {
+    client: reqwest::Client,
+    api_key: String,
+    domain: String,
+    from: String,
+    to: Recipients,
+    operator_to: Vec<String>,
+    templates: Arc<EmailTemplates>,
+}
+
+impl MailgunNotifier {
+    pub fn from_env(
+        campaigns: &[Campaign],
+        templates: Arc<EmailTemplates>,
+    ) -> Result<Option<Self>> {
+        let Ok(api_key) = env::var("MAILGUN_API_KEY") else {
+            return Ok(None);
+        };
+        let to = Recipients::from_env(campaigns)?;
+
+        Ok(Some(MailgunNotifier {
+            client: reqwest::Client::new(),
+            api_key,
+            domain: env::var("MAILGUN_DOMAIN")
+                .context("MAILGUN_DOMAIN environment variable not set")?,
+            from: env::var("FROM_EMAIL").unwrap_or_else(|_| "noreply@inflyte.com".to_string()),
+            operator_to: env::var("OPERATOR_EMAIL")
+                .or_else(|_| env::var("RECIPIENT_EMAIL"))
+                .map(|addresses| split_addresses(&addresses))
+                .unwrap_or_default(),
+            to,
+            templates,
+        }))
+    }
+
+    /// Send an email through the Mailgun API
+    async fn send(&self, to: &[String], message: EmailMessage) -> Result<()> {
+        let mailgun_url = format!("https://api.mailgun.net/v3/{}/messages", self.domain);
+
+        let form = reqwest::multipart::Form::new()
+            .text("from", self.from.clone())
+            .text("to", to.join(", "))
+            .text("subject", message.subject)
+            .text("text", message.text)
+            .text("html", message.html);
+
+        let response = self
+            .client
+            .post(&mailgun_url)
+            .basic_auth("api", Some(&self.api_key))
+            .multipart(form)
+            .send()
+            .await
+            .context("Failed to send email via Mailgun")?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            anyhow::bail!("Mailgun API error: {}", error_text)
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for MailgunNotifier {
+    fn name(&self) -> &'static str {
+        "mailgun"
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "Mailgun via {} ({} -> {}, operator alerts -> {})",
+            self.domain,
+            self.from,
+            self.to,
+            self.operator_to.join(", ")
+        )
+    }
+
+    async fn notify(&self, campaign: &Campaign, events: &[SupportEvent]) -> Result<()> {
+        let to = self.to.for_campaign(campaign);
+        if to.is_empty() {
+            debug!(campaign = %campaign.name, "No email recipients for campaign, skipping");
+            return Ok(());
+        }
+        self.send(&to, self.templates.support_alert(campaign, events)?)
+            .await
+    }
+
+    async fn notify_digest(&self, report: &DigestReport) -> Result<()> {
+        for (recipient, digest) in self.to.split_digest(report) {
+            self.send(&[recipient], self.templates.digest(&digest)?)
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn targets(&self, campaign: &Campaign) -> Vec<String> {
+        self.to.for_campaign(campaign)
+    }
+
+    async fn notify_target(
+        &self,
+        campaign: &Campaign,
+        address: &str,
+        events: &[SupportEvent],
+    ) -> Result<()> {
+        self.send(
+            &[address.to_string()],
+            self.templates.support_alert(campaign, events)?,
+        )
+        .await
+    }
+
+    async fn notify_digest_target(&self, report: &DigestReport, address: &str) -> Result<()> {
+        self.send(&[address.to_string()], self.templates.digest(report)?)
+            .await
+    }
+
+    async fn notify_operator(&self, campaign: &Campaign, issues: &[HealthIssue]) -> Result<()> {
+        if self.operator_to.is_empty() {
+            debug!(campaign = %campaign.name, "No operator email, skipping operator alert");
+            return Ok(());
+        }
+        self.send(
+            &self.operator_to,
+            self.templates.operator_alert(campaign, issues)?,
+        )
+        .await
+    }
+}
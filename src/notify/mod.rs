@@ -0,0 +1,337 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::fmt;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::diff::{EventKind, SupportEvent};
+use crate::digest::DigestReport;
+use crate::health::HealthIssue;
+use crate::{Campaign, DjSupport};
+
+mod discord;
+mod email;
+mod mailgun;
+mod slack;
+mod smtp;
+mod telegram;
+mod webhook;
+
+pub use discord::DiscordNotifier;
+pub use email::{EmailMessage, EmailTemplates};
+pub use mailgun::MailgunNotifier;
+pub use slack::SlackNotifier;
+pub use smtp::SmtpNotifier;
+pub use telegram::TelegramNotifier;
+pub use webhook::WebhookNotifier;
+
+/// Channel names a campaign's `channels` setting may list; `email` stands for both
+/// email channels
+const CHANNEL_NAMES: &[&str] = &[
+    "email", "mailgun", "smtp", "slack", "discord", "telegram", "webhook",
+];
+
+/// Whether a campaign's alerts go to the named channel
+fn routes_to(campaign: &Campaign, notifier: &str) -> bool {
+    match &campaign.settings.channels {
+        None => true,
+        Some(channels) => channels.iter().any(|channel| {
+            channel.eq_ignore_ascii_case(notifier)
+                || (channel.eq_ignore_ascii_case("email") && matches!(notifier, "mailgun" | "smtp"))
+        }),
+    }
+}
+
+/// A channel that support alerts are delivered to
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Short channel name used in logs
+    fn name(&self) -> &'static str;
+
+    /// One-line description of where alerts go, logged at startup
+    fn describe(&self) -> String;
+
+    /// Deliver the detected support changes of a campaign
+    async fn notify(&self, campaign: &Campaign, events: &[SupportEvent]) -> Result<()>;
+
+    /// The separate destinations of a campaign's alerts, e.g. chat IDs, each delivered and
+    /// retried on its own; empty for channels that deliver everything in one go
+    fn targets(&self, _campaign: &Campaign) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Deliver the detected support changes of a campaign to one of its [`targets`]
+    ///
+    /// [`targets`]: Notifier::targets
+    async fn notify_target(
+        &self,
+        campaign: &Campaign,
+        _target: &str,
+        events: &[SupportEvent],
+    ) -> Result<()> {
+        self.notify(campaign, events).await
+    }
+
+    /// Deliver the changes collected across campaigns since the previous digest
+    ///
+    /// Channels without a digest format of their own deliver each campaign's changes as
+    /// a regular alert.
+    async fn notify_digest(&self, report: &DigestReport) -> Result<()> {
+        for entry in &report.entries {
+            self.notify(&entry.campaign, &entry.events).await?;
+        }
+        Ok(())
+    }
+
+    /// Deliver a digest to one of its campaigns' [`targets`]
+    ///
+    /// [`targets`]: Notifier::targets
+    async fn notify_digest_target(&self, report: &DigestReport, target: &str) -> Result<()> {
+        for entry in &report.entries {
+            self.notify_target(&entry.campaign, target, &entry.events)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Tell the operator that a campaign page no longer parses as expected
+    ///
+    /// Channels aimed at artists and labels ignore these by default.
+    async fn notify_operator(&self, _campaign: &Campaign, _issues: &[HealthIssue]) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Every enabled notification channel; alerts fan out to all of them, or to the channels
+/// a campaign is routed to
+#[derive(Clone, Default)]
+pub struct Notifiers {
+    notifiers: Vec<Arc<dyn Notifier>>,
+}
+
+impl fmt::Debug for Notifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.notifiers.iter().map(|n| n.name()))
+            .finish()
+    }
+}
+
+impl Notifiers {
+    /// Enable each channel whose environment variables (or campaign settings) are set;
+    /// none at all is allowed
+    pub fn from_env(campaigns: &[Campaign]) -> Result<Self> {
+        for campaign in campaigns {
+            for channel in campaign.settings.channels.iter().flatten() {
+                if !CHANNEL_NAMES.contains(&channel.to_lowercase().as_str()) {
+                    anyhow::bail!(
+                        "Unknown channel '{}' in settings of campaign {}: expected one of {}",
+                        channel,
+                        campaign.name,
+                        CHANNEL_NAMES.join(", ")
+                    );
+                }
+            }
+        }
+
+        let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+
+        // Shared by every email channel; parsed up front so broken templates fail at startup
+        let templates = Arc::new(EmailTemplates::from_env()?);
+        if let Some(mailgun) = MailgunNotifier::from_env(campaigns, templates.clone())? {
+            notifiers.push(Arc::new(mailgun));
+        }
+        if let Some(smtp) = SmtpNotifier::from_env(campaigns, templates)? {
+            notifiers.push(Arc::new(smtp));
+        }
+        if let Some(slack) = SlackNotifier::from_env(campaigns) {
+            notifiers.push(Arc::new(slack));
+        }
+        if let Some(discord) = DiscordNotifier::from_env(campaigns) {
+            notifiers.push(Arc::new(discord));
+        }
+        if let Some(telegram) = TelegramNotifier::from_env(campaigns)? {
+            notifiers.push(Arc::new(telegram));
+        }
+        if let Some(webhook) = WebhookNotifier::from_env(campaigns)? {
+            notifiers.push(Arc::new(webhook));
+        }
+
+        Ok(Notifiers { notifiers })
+    }
+
+    /// Add a channel, e.g. one of an embedding service's own
+    pub fn push(&mut self, notifier: Arc<dyn Notifier>) {
+        self.notifiers.push(notifier);
+    }
+
+    pub fn log_summary(&self) {
+        if self.notifiers.is_empty() {
+            info!("  Notifiers: none (alerts are only logged)");
+        }
+        for notifier in &self.notifiers {
+            info!("  Notifier: {}", notifier.describe());
+        }
+    }
+
+    /// Names of the enabled channels a campaign's alerts go to
+    pub fn routed(&self, campaign: &Campaign) -> Vec<&'static str> {
+        self.notifiers
+            .iter()
+            .map(|n| n.name())
+            .filter(|name| routes_to(campaign, name))
+            .collect()
+    }
+
+    fn find(&self, channel: &str) -> Result<&Arc<dyn Notifier>> {
+        self.notifiers
+            .iter()
+            .find(|n| n.name() == channel)
+            .with_context(|| format!("Notifier {} is not enabled", channel))
+    }
+
+    /// The separate destinations of a campaign's alerts on the named channel; empty when
+    /// the channel delivers everything in one go or is not enabled
+    pub fn targets(&self, channel: &str, campaign: &Campaign) -> Vec<String> {
+        self.find(channel)
+            .map(|notifier| notifier.targets(campaign))
+            .unwrap_or_default()
+    }
+
+    /// Where a campaign's alerts go: every channel it is routed to, once per target for
+    /// channels that have them
+    pub fn destinations(&self, campaign: &Campaign) -> Vec<(&'static str, Option<String>)> {
+        let mut destinations = Vec::new();
+        for channel in self.routed(campaign) {
+            let targets = self.targets(channel, campaign);
+            if targets.is_empty() {
+                destinations.push((channel, None));
+            }
+            destinations.extend(targets.into_iter().map(|target| (channel, Some(target))));
+        }
+        destinations
+    }
+
+    /// Deliver support changes to the named channel, or only to one of its targets
+    pub async fn deliver(
+        &self,
+        channel: &str,
+        target: Option<&str>,
+        campaign: &Campaign,
+        events: &[SupportEvent],
+    ) -> Result<()> {
+        let notifier = self.find(channel)?;
+        match target {
+            Some(target) => notifier.notify_target(campaign, target, events).await,
+            None => notifier.notify(campaign, events).await,
+        }
+    }
+
+    /// Deliver a digest to the named channel, or only to one of its targets
+    pub async fn deliver_digest(
+        &self,
+        channel: &str,
+        target: Option<&str>,
+        report: &DigestReport,
+    ) -> Result<()> {
+        let notifier = self.find(channel)?;
+        match target {
+            Some(target) => notifier.notify_digest_target(report, target).await,
+            None => notifier.notify_digest(report).await,
+        }
+    }
+
+    /// Deliver an operator alert to every channel that handles them, whatever the
+    /// campaign's routing
+    pub async fn notify_operator(&self, campaign: &Campaign, issues: &[HealthIssue]) {
+        for notifier in &self.notifiers {
+            if let Err(e) = notifier.notify_operator(campaign, issues).await {
+                error!(notifier = notifier.name(), error = %e, "Failed to send operator alert");
+            }
+        }
+    }
+}
+
+/// Format a DJ as a single plain-text line (name, stars and comment)
+pub fn format_dj_line(dj: &DjSupport) -> String {
+    let mut line = dj.name.clone();
+    if let Some(stars) = dj.stars {
+        line.push_str(&format!(" {}", "⭐".repeat(stars as usize)));
+    }
+    if let Some(comment) = &dj.comment {
+        line.push_str(&format!(" - \"{}\"", comment));
+    }
+    line
+}
+
+/// Describe what an update event changed, e.g. `was 3 stars`
+pub fn describe_previous(event: &SupportEvent) -> Option<String> {
+    let previous = event.previous.as_ref()?;
+    match event.kind {
+        EventKind::CommentAdded => Some("comment added".to_string()),
+        EventKind::CommentEdited => Some(match &previous.comment {
+            Some(comment) if event.dj.comment.is_some() => format!("was \"{}\"", comment),
+            Some(comment) => format!("comment removed, was \"{}\"", comment),
+            None => "comment edited".to_string(),
+        }),
+        EventKind::RatingChanged => Some(match previous.stars {
+            Some(stars) => format!("was {} stars", stars),
+            None => "was unrated".to_string(),
+        }),
+        EventKind::New | EventKind::Removed => None,
+    }
+}
+
+/// Format an event as a single plain-text line
+pub fn format_event_line(event: &SupportEvent) -> String {
+    match describe_previous(event) {
+        Some(note) => format!("{} ({})", format_dj_line(&event.dj), note),
+        None => format_dj_line(&event.dj),
+    }
+}
+
+/// Marker shown in front of a DJ for each kind of change
+pub fn event_marker(kind: EventKind) -> &'static str {
+    match kind {
+        EventKind::New => "✨",
+        EventKind::Removed => "❌",
+        _ => "💬",
+    }
+}
+
+/// Count events by kind, e.g. `2 new, 1 updated`
+pub fn summarize(events: &[SupportEvent]) -> String {
+    let count = |pred: fn(&SupportEvent) -> bool| events.iter().filter(|e| pred(e)).count();
+    [
+        (count(|e| e.kind == EventKind::New), "new"),
+        (count(|e| e.kind.is_update()), "updated"),
+        (count(|e| e.kind == EventKind::Removed), "removed"),
+    ]
+    .into_iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, label)| format!("{} {}", n, label))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// Send a request to a chat or webhook endpoint, failing with `what` as context
+///
+/// Webhook and bot API URLs carry their token, so the URL is left out of the error, which
+/// ends up in logs and the outbox.
+async fn send(request: reqwest::RequestBuilder, what: &str) -> Result<reqwest::Response> {
+    request
+        .send()
+        .await
+        .map_err(|e| e.without_url())
+        .context(what.to_string())
+}
+
+/// Shorten text to at most `max` characters, marking the cut with an ellipsis
+pub fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
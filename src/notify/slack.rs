@@ -0,0 +1,246 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::env;
+use tracing::debug;
+
+use super::{Notifier, describe_previous, event_marker, summarize, truncate};
+use crate::Campaign;
+use crate::diff::SupportEvent;
+
+/// Slack allows 50 blocks per message; header, summary, divider and button take four
+const MAX_EVENTS_PER_MESSAGE: usize = 40;
+
+/// Posts support changes to Slack incoming webhooks as Block Kit messages
+///
+/// Each campaign posts to its own `slack_webhook_url` from the campaign settings, falling
+/// back to `SLACK_WEBHOOK_URL`. Campaigns with neither are skipped.
+pub struct SlackNotifier {
+    client: reqwest::Client,
+    default_webhook_url: Option<String>,
+}
+
+impl SlackNotifier {
+    /// Enabled by `SLACK_WEBHOOK_URL` or by any campaign with its own webhook
+    pub fn from_env(campaigns: &[Campaign]) -> Option<Self> {
+        let default_webhook_url = env::var("SLACK_WEBHOOK_URL").ok();
+        let any_campaign_webhook = campaigns
+            .iter()
+            .any(|c| c.settings.slack_webhook_url.is_some());
+
+        (default_webhook_url.is_some() || any_campaign_webhook).then(|| SlackNotifier {
+            client: reqwest::Client::new(),
+            default_webhook_url,
+        })
+    }
+
+    fn webhook_url<'a>(&'a self, campaign: &'a Campaign) -> Option<&'a str> {
+        campaign
+            .settings
+            .slack_webhook_url
+            .as_deref()
+            .or(self.default_webhook_url.as_deref())
+    }
+}
+
+/// Escape the characters Slack mrkdwn treats as control sequences
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// One section block per event: marker, name, stars, quoted comment and what changed
+fn event_block(event: &SupportEvent) -> Value {
+    let dj = &event.dj;
+    let mut text = format!("{} *{}*", event_marker(event.kind), escape(&dj.name));
+    if let Some(stars) = dj.stars {
+        text.push_str(&format!("  {}", "⭐".repeat(stars as usize)));
+    }
+    if let Some(comment) = &dj.comment {
+        text.push_str(&format!("\n>{}", escape(&truncate(comment, 2500))));
+    }
+    if let Some(note) = describe_previous(event) {
+        text.push_str(&format!("\n_{}_", escape(&note)));
+    }
+
+    json!({
+        "type": "section",
+        "text": { "type": "mrkdwn", "text": text },
+    })
+}
+
+/// Split the alert into Block Kit messages within Slack's block limit, each summarizing
+/// all of the events
+fn build_messages(campaign: &Campaign, events: &[SupportEvent]) -> Vec<Value> {
+    events
+        .chunks(MAX_EVENTS_PER_MESSAGE)
+        .map(|batch| build_message(campaign, batch, events))
+        .collect()
+}
+
+/// Build a Block Kit message for a batch of events
+fn build_message(campaign: &Campaign, events: &[SupportEvent], all: &[SupportEvent]) -> Value {
+    let title = campaign.display_name();
+    let summary = summarize(all);
+
+    let mut summary_block = json!({
+        "type": "section",
+        "text": {
+            "type": "mrkdwn",
+            "text": format!("*DJ support changes:* {}", summary),
+        },
+    });
+    if let Some(artwork_url) = &campaign.metadata.artwork_url {
+        summary_block["accessory"] = json!({
+            "type": "image",
+            "image_url": artwork_url,
+            "alt_text": "Artwork",
+        });
+    }
+
+    let mut blocks = vec![
+        json!({
+            "type": "header",
+            "text": {
+                "type": "plain_text",
+                "text": truncate(&format!("🎵 {}", title), 150),
+                "emoji": true,
+            },
+        }),
+        summary_block,
+        json!({ "type": "divider" }),
+    ];
+    blocks.extend(events.iter().map(event_block));
+    blocks.push(json!({
+        "type": "actions",
+        "elements": [{
+            "type": "button",
+            "text": { "type": "plain_text", "text": "View Campaign" },
+            "url": campaign.url,
+        }],
+    }));
+
+    json!({
+        // Shown in notifications and clients that can't render blocks
+        "text": format!("🎵 {}: {}", title, summary),
+        "blocks": blocks,
+    })
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    fn describe(&self) -> String {
+        match self.default_webhook_url {
+            Some(_) => "Slack (default webhook + per-campaign webhooks)".to_string(),
+            None => "Slack (per-campaign webhooks only)".to_string(),
+        }
+    }
+
+    async fn notify(&self, campaign: &Campaign, events: &[SupportEvent]) -> Result<()> {
+        let Some(webhook_url) = self.webhook_url(campaign) else {
+            debug!(campaign = %campaign.name, "No Slack webhook for campaign, skipping");
+            return Ok(());
+        };
+
+        for message in build_messages(campaign, events) {
+            let response = super::send(
+                self.client.post(webhook_url).json(&message),
+                "Failed to post to Slack",
+            )
+            .await?;
+
+            if !response.status().is_success() {
+                let error_text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string());
+                anyhow::bail!("Slack webhook error: {}", error_text);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::EventKind;
+    use crate::testing::{campaign, dj, event, new_support};
+
+    #[test]
+    fn block_kit_message() {
+        let mut campaign = campaign("pmqtne");
+        campaign.metadata.artist = Some("Vela".to_string());
+        campaign.metadata.title = Some("Afterglow".to_string());
+        campaign.metadata.artwork_url = Some("https://example.com/cover.jpg".to_string());
+        let events = [
+            event(
+                EventKind::New,
+                dj("Dixon", Some("<b>Huge</b> & more"), Some(5)),
+                None,
+            ),
+            event(
+                EventKind::RatingChanged,
+                dj("Kölsch", None, Some(4)),
+                Some(dj("Kölsch", None, Some(3))),
+            ),
+        ];
+
+        let messages = build_messages(&campaign, &events);
+        assert_eq!(messages.len(), 1);
+        let message = &messages[0];
+        assert_eq!(message["text"], "🎵 Vela - Afterglow: 1 new, 1 updated");
+
+        let blocks = message["blocks"].as_array().unwrap();
+        let types: Vec<&str> = blocks.iter().map(|b| b["type"].as_str().unwrap()).collect();
+        assert_eq!(
+            types,
+            [
+                "header", "section", "divider", "section", "section", "actions"
+            ]
+        );
+        assert_eq!(blocks[0]["text"]["text"], "🎵 Vela - Afterglow");
+        assert_eq!(
+            blocks[1]["accessory"]["image_url"],
+            "https://example.com/cover.jpg"
+        );
+        assert_eq!(
+            blocks[3]["text"]["text"],
+            "✨ *Dixon*  ⭐⭐⭐⭐⭐\n>&lt;b&gt;Huge&lt;/b&gt; &amp; more"
+        );
+        assert_eq!(
+            blocks[4]["text"]["text"],
+            "💬 *Kölsch*  ⭐⭐⭐⭐\n_was 3 stars_"
+        );
+        assert_eq!(
+            blocks[5]["elements"][0]["url"],
+            "https://inflyteapp.com/r/pmqtne"
+        );
+    }
+
+    #[test]
+    fn long_alerts_are_split_within_the_block_limit() {
+        let campaign = campaign("pmqtne");
+        let events: Vec<SupportEvent> =
+            (0..85).map(|i| new_support(&format!("DJ {}", i))).collect();
+
+        let messages = build_messages(&campaign, &events);
+        let event_blocks: Vec<usize> = messages
+            .iter()
+            .map(|m| m["blocks"].as_array().unwrap().len() - 4)
+            .collect();
+        assert_eq!(event_blocks, [40, 40, 5]);
+        for message in &messages {
+            assert!(message["blocks"].as_array().unwrap().len() <= 50);
+            // Every part summarizes the whole alert
+            assert_eq!(message["text"], "🎵 pmqtne: 85 new");
+        }
+        assert_eq!(messages[1]["blocks"][3]["text"]["text"], "✨ *DJ 40*");
+    }
+}
@@ -0,0 +1,272 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::env;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::Notifier;
+use super::email::{EmailMessage, EmailTemplates, Recipients};
+use crate::Campaign;
+use crate::diff::SupportEvent;
+use crate::digest::DigestReport;
+use crate::health::HealthIssue;
+
+/// How the connection to the SMTP server is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SmtpSecurity {
+    /// Upgrade a plain connection with STARTTLS (usually port 587)
+    StartTls,
+    /// TLS from the first byte (usually port 465)
+    Tls,
+    /// No encryption, e.g. for a local MailHog
+    None,
+}
+
+impl SmtpSecurity {
+    fn from_env() -> Result<Self> {
+        env::var("SMTP_SECURITY")
+            .unwrap_or_else(|_| "starttls".to_string())
+            .parse()
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SmtpSecurity::StartTls => "STARTTLS",
+            SmtpSecurity::Tls => "TLS",
+            SmtpSecurity::None => "unencrypted",
+        }
+    }
+
+    fn default_port(self) -> u16 {
+        match self {
+            SmtpSecurity::StartTls => 587,
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::None => 25,
+        }
+    }
+}
+
+impl FromStr for SmtpSecurity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "starttls" => Ok(SmtpSecurity::StartTls),
+            "tls" | "ssl" => Ok(SmtpSecurity::Tls),
+            "none" => Ok(SmtpSecurity::None),
+            other => anyhow::bail!(
+                "Invalid SMTP_SECURITY '{}': expected starttls, tls or none",
+                other
+            ),
+        }
+    }
+}
+
+/// Sends alert emails through an SMTP server
+///
+/// Enabled by `SMTP_HOST`; `RECIPIENT_EMAIL` (or campaign recipients) is then required.
+/// Uses the same subject, HTML and text bodies as the Mailgun channel.
+pub struct SmtpNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    host: String,
+    port: u16,
+    security: SmtpSecurity,
+    from: Mailbox,
+    to: Recipients,
+    operator_to: Vec<Mailbox>,
+    templates: Arc<EmailTemplates>,
+}
+
+/// Parse a comma-separated list of addresses
+fn parse_mailboxes(var: &str, addresses: &str) -> Result<Vec<Mailbox>> {
+    addresses
+        .split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(|address| {
+            address
+                .parse()
+                .with_context(|| format!("Invalid address in {}: {}", var, address))
+        })
+        .collect()
+}
+
+impl SmtpNotifier {
+    pub fn from_env(
+        campaigns: &[Campaign],
+        templates: Arc<EmailTemplates>,
+    ) -> Result<Option<Self>> {
+        let Ok(host) = env::var("SMTP_HOST") else {
+            return Ok(None);
+        };
+        let security = SmtpSecurity::from_env()?;
+        let port = match env::var("SMTP_PORT") {
+            Ok(port) => port
+                .parse()
+                .with_context(|| format!("Invalid SMTP_PORT: {}", port))?,
+            Err(_) => security.default_port(),
+        };
+
+        let to = Recipients::from_env(campaigns)?;
+        // Bad addresses should fail now rather than on the first alert
+        for address in to.all(campaigns) {
+            parse_mailboxes("recipients", address)?;
+        }
+        let operator_to = match env::var("OPERATOR_EMAIL") {
+            Ok(operators) => parse_mailboxes("OPERATOR_EMAIL", &operators)?,
+            Err(_) => parse_mailboxes(
+                "RECIPIENT_EMAIL",
+                &env::var("RECIPIENT_EMAIL").unwrap_or_default(),
+            )?,
+        };
+        let from = env::var("FROM_EMAIL")
+            .unwrap_or_else(|_| "noreply@inflyte.com".to_string())
+            .parse()
+            .context("Invalid FROM_EMAIL")?;
+
+        let mut builder = match security {
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)
+                .context("Failed to set up STARTTLS for SMTP")?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&host)
+                .context("Failed to set up TLS for SMTP")?,
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host),
+        }
+        .port(port);
+        if let Ok(username) = env::var("SMTP_USERNAME") {
+            let password = env::var("SMTP_PASSWORD")
+                .context("SMTP_PASSWORD must be set with SMTP_USERNAME")?;
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(Some(SmtpNotifier {
+            transport: builder.build(),
+            host,
+            port,
+            security,
+            from,
+            to,
+            operator_to,
+            templates,
+        }))
+    }
+
+    /// Send an email as a text/HTML alternative
+    async fn send(&self, to: &[Mailbox], message: EmailMessage) -> Result<()> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(message.subject);
+        for mailbox in to {
+            builder = builder.to(mailbox.clone());
+        }
+        let email = builder
+            .multipart(MultiPart::alternative_plain_html(
+                message.text,
+                message.html,
+            ))
+            .context("Failed to build email")?;
+
+        self.transport
+            .send(email)
+            .await
+            .with_context(|| format!("Failed to send email via SMTP server {}", self.host))?;
+        Ok(())
+    }
+}
+
+/// Addresses joined for logging
+fn join(mailboxes: &[Mailbox]) -> String {
+    mailboxes
+        .iter()
+        .map(|m| m.email.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    fn name(&self) -> &'static str {
+        "smtp"
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "SMTP via {}:{}, {} ({} -> {}, operator alerts -> {})",
+            self.host,
+            self.port,
+            self.security.label(),
+            self.from.email,
+            self.to,
+            join(&self.operator_to)
+        )
+    }
+
+    async fn notify(&self, campaign: &Campaign, events: &[SupportEvent]) -> Result<()> {
+        let to = parse_mailboxes("recipients", &self.to.for_campaign(campaign).join(","))?;
+        if to.is_empty() {
+            debug!(campaign = %campaign.name, "No email recipients for campaign, skipping");
+            return Ok(());
+        }
+        self.send(&to, self.templates.support_alert(campaign, events)?)
+            .await
+    }
+
+    async fn notify_digest(&self, report: &DigestReport) -> Result<()> {
+        for (recipient, digest) in self.to.split_digest(report) {
+            let to = parse_mailboxes("recipients", &recipient)?;
+            self.send(&to, self.templates.digest(&digest)?).await?;
+        }
+        Ok(())
+    }
+
+    fn targets(&self, campaign: &Campaign) -> Vec<String> {
+        self.to.for_campaign(campaign)
+    }
+
+    async fn notify_target(
+        &self,
+        campaign: &Campaign,
+        address: &str,
+        events: &[SupportEvent],
+    ) -> Result<()> {
+        let to = parse_mailboxes("recipients", address)?;
+        self.send(&to, self.templates.support_alert(campaign, events)?)
+            .await
+    }
+
+    async fn notify_digest_target(&self, report: &DigestReport, address: &str) -> Result<()> {
+        let to = parse_mailboxes("recipients", address)?;
+        self.send(&to, self.templates.digest(report)?).await
+    }
+
+    async fn notify_operator(&self, campaign: &Campaign, issues: &[HealthIssue]) -> Result<()> {
+        if self.operator_to.is_empty() {
+            debug!(campaign = %campaign.name, "No operator email, skipping operator alert");
+            return Ok(());
+        }
+        self.send(
+            &self.operator_to,
+            self.templates.operator_alert(campaign, issues)?,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn security_modes_and_default_ports() {
+        let port = |mode: &str| mode.parse::<SmtpSecurity>().unwrap().default_port();
+        assert_eq!(port("starttls"), 587);
+        assert_eq!(port("STARTTLS"), 587);
+        assert_eq!(port("tls"), 465);
+        assert_eq!(port("ssl"), 465);
+        assert_eq!(port("none"), 25);
+        assert!("plain".parse::<SmtpSecurity>().is_err());
+    }
+}
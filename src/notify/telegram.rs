@@ -0,0 +1,303 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::env;
+use tracing::debug;
+
+use super::{Notifier, describe_previous, event_marker, summarize, truncate};
+use crate::Campaign;
+use crate::diff::SupportEvent;
+
+const DEFAULT_API_BASE: &str = "https://api.telegram.org";
+
+/// Telegram rejects messages longer than 4096 characters
+const MAX_MESSAGE_CHARS: usize = 4096;
+
+/// Sends support changes to Telegram chats through a bot
+///
+/// Each campaign goes to its own `telegram_chat_ids` from the campaign settings, falling
+/// back to `TELEGRAM_CHAT_ID`. Campaigns with neither are skipped. Every chat is a separate
+/// target, so a chat that fails is retried without resending to the others.
+pub struct TelegramNotifier {
+    client: reqwest::Client,
+    api_base: String,
+    bot_token: String,
+    default_chat_ids: Vec<String>,
+}
+
+/// The envelope of every Bot API response
+#[derive(Deserialize)]
+struct ApiResponse {
+    ok: bool,
+    description: Option<String>,
+}
+
+impl TelegramNotifier {
+    /// Enabled by `TELEGRAM_BOT_TOKEN`; fails if campaigns list chats without a bot token
+    pub fn from_env(campaigns: &[Campaign]) -> Result<Option<Self>> {
+        let Ok(bot_token) = env::var("TELEGRAM_BOT_TOKEN") else {
+            if let Some(campaign) = campaigns
+                .iter()
+                .find(|c| !c.settings.telegram_chat_ids.is_empty())
+            {
+                anyhow::bail!(
+                    "Campaign {} has telegram_chat_ids but TELEGRAM_BOT_TOKEN is not set",
+                    campaign.name
+                );
+            }
+            return Ok(None);
+        };
+
+        let default_chat_ids = env::var("TELEGRAM_CHAT_ID")
+            .map(|ids| {
+                ids.split(',')
+                    .map(|id| id.trim().to_string())
+                    .filter(|id| !id.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let api_base = env::var("TELEGRAM_API_BASE")
+            .unwrap_or_else(|_| DEFAULT_API_BASE.to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        Ok(Some(TelegramNotifier {
+            client: reqwest::Client::new(),
+            api_base,
+            bot_token,
+            default_chat_ids,
+        }))
+    }
+
+    fn chat_ids(&self, campaign: &Campaign) -> Vec<String> {
+        if campaign.settings.telegram_chat_ids.is_empty() {
+            self.default_chat_ids.clone()
+        } else {
+            campaign
+                .settings
+                .telegram_chat_ids
+                .iter()
+                .map(|chat| chat.to.clone())
+                .collect()
+        }
+    }
+
+    async fn send_message(&self, chat_id: &str, text: &str) -> Result<()> {
+        let request = self
+            .client
+            .post(format!(
+                "{}/bot{}/sendMessage",
+                self.api_base, self.bot_token
+            ))
+            .json(&json!({
+                "chat_id": chat_id,
+                "text": text,
+                "parse_mode": "MarkdownV2",
+                "link_preview_options": { "is_disabled": true },
+            }));
+        let response = super::send(request, "Failed to send Telegram message").await?;
+
+        let status = response.status();
+        let body: Option<ApiResponse> = response.json().await.ok();
+        match body {
+            Some(body) if body.ok => Ok(()),
+            Some(body) => anyhow::bail!(
+                "Telegram API error for chat {}: {}",
+                chat_id,
+                body.description
+                    .unwrap_or_else(|| "Unknown error".to_string())
+            ),
+            None => anyhow::bail!("Telegram API error for chat {}: HTTP {}", chat_id, status),
+        }
+    }
+}
+
+/// Escape every character MarkdownV2 reserves for formatting
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "_*[]()~`>#+-=|{}.!\\".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escape a URL for use inside a MarkdownV2 link
+fn escape_url(url: &str) -> String {
+    url.replace('\\', "\\\\").replace(')', "\\)")
+}
+
+/// One paragraph per event: marker, bold name, stars, italic comment and what changed
+fn format_event(event: &SupportEvent) -> String {
+    let dj = &event.dj;
+    let mut text = format!("{} *{}*", event_marker(event.kind), escape(&dj.name));
+    if let Some(stars) = dj.stars {
+        text.push_str(&format!(" {}", "⭐".repeat(stars as usize)));
+    }
+    if let Some(comment) = &dj.comment {
+        text.push_str(&format!("\n_{}_", escape(&truncate(comment, 1000))));
+    }
+    if let Some(note) = describe_previous(event) {
+        text.push_str(&format!("\n\\({}\\)", escape(&note)));
+    }
+    text
+}
+
+/// Split the alert into messages within Telegram's length limit, each opening with the
+/// track title and closing with the campaign link
+fn build_messages(campaign: &Campaign, events: &[SupportEvent]) -> Vec<String> {
+    let header = format!(
+        "🎵 *{}*\n{}",
+        escape(&truncate(&campaign.display_name(), 200)),
+        escape(&summarize(events))
+    );
+    let footer = format!("[View campaign]({})", escape_url(&campaign.url));
+    let room = MAX_MESSAGE_CHARS - header.chars().count() - footer.chars().count() - 4;
+
+    let mut bodies: Vec<String> = Vec::new();
+    let mut body = String::new();
+    for paragraph in events.iter().map(format_event) {
+        let needed = paragraph.chars().count() + 2;
+        if !body.is_empty() && body.chars().count() + needed > room {
+            bodies.push(std::mem::take(&mut body));
+        }
+        body.push_str(&paragraph);
+        body.push_str("\n\n");
+    }
+    bodies.push(body);
+
+    bodies
+        .into_iter()
+        .map(|body| format!("{}\n\n{}{}", header, body, footer))
+        .collect()
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    fn describe(&self) -> String {
+        match self.default_chat_ids.len() {
+            0 => "Telegram (per-campaign chats only)".to_string(),
+            n => format!("Telegram ({} default chat(s) + per-campaign chats)", n),
+        }
+    }
+
+    async fn notify(&self, campaign: &Campaign, events: &[SupportEvent]) -> Result<()> {
+        let chat_ids = self.chat_ids(campaign);
+        if chat_ids.is_empty() {
+            debug!(campaign = %campaign.name, "No Telegram chat for campaign, skipping");
+            return Ok(());
+        }
+
+        let mut failures = Vec::new();
+        for chat_id in &chat_ids {
+            if let Err(e) = self.notify_target(campaign, chat_id, events).await {
+                failures.push(format!("{:#}", e));
+            }
+        }
+
+        if !failures.is_empty() {
+            anyhow::bail!(
+                "{} Telegram chat(s) failed: {}",
+                failures.len(),
+                failures.join("; ")
+            );
+        }
+        Ok(())
+    }
+
+    fn targets(&self, campaign: &Campaign) -> Vec<String> {
+        self.chat_ids(campaign)
+    }
+
+    async fn notify_target(
+        &self,
+        campaign: &Campaign,
+        chat_id: &str,
+        events: &[SupportEvent],
+    ) -> Result<()> {
+        for message in build_messages(campaign, events) {
+            self.send_message(chat_id, &message).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::EventKind;
+    use crate::testing::{campaign, dj, event};
+
+    #[test]
+    fn markdown_v2_escaping() {
+        assert_eq!(escape("Dixon"), "Dixon");
+        assert_eq!(
+            escape("DJ_Tennis *live* [b2b] (3h) ~ `x` > #1 + - = | {} . ! \\"),
+            "DJ\\_Tennis \\*live\\* \\[b2b\\] \\(3h\\) \\~ \\`x\\` \\> \\#1 \\+ \\- \\= \\| \\{\\} \\. \\! \\\\"
+        );
+        assert_eq!(escape("Âme ⭐"), "Âme ⭐");
+
+        // Inside a link only `)` and `\` need escaping
+        assert_eq!(
+            escape_url("https://inflyteapp.com/r/a_b.c(1)"),
+            "https://inflyteapp.com/r/a_b.c(1\\)"
+        );
+        assert_eq!(escape_url("https://x.com/a\\b"), "https://x.com/a\\\\b");
+    }
+
+    #[test]
+    fn short_alerts_are_one_message() {
+        let mut campaign = campaign("pmqtne");
+        campaign.metadata.artist = Some("Vela".to_string());
+        campaign.metadata.title = Some("Afterglow".to_string());
+        let events = [event(
+            EventKind::CommentAdded,
+            dj("Dixon", Some("Huge!"), Some(5)),
+            Some(dj("Dixon", None, Some(5))),
+        )];
+
+        assert_eq!(
+            build_messages(&campaign, &events),
+            ["🎵 *Vela \\- Afterglow*\n1 updated\n\n\
+              💬 *Dixon* ⭐⭐⭐⭐⭐\n_Huge\\!_\n\\(comment added\\)\n\n\
+              [View campaign](https://inflyteapp.com/r/pmqtne)"]
+        );
+    }
+
+    #[test]
+    fn long_alerts_are_split_at_4096_characters() {
+        let campaign = campaign("pmqtne");
+        let comment = "x".repeat(1500);
+        let events: Vec<SupportEvent> = (0..10)
+            .map(|i| {
+                event(
+                    EventKind::New,
+                    dj(&format!("DJ {}", i), Some(&comment), None),
+                    None,
+                )
+            })
+            .collect();
+
+        let messages = build_messages(&campaign, &events);
+        // Comments are cut to 1000 characters, so three events fit in a message
+        assert_eq!(messages.len(), 4);
+        for message in &messages {
+            assert!(message.chars().count() <= MAX_MESSAGE_CHARS);
+            assert!(message.starts_with("🎵 *pmqtne*\n10 new\n\n"));
+            assert!(message.ends_with("[View campaign](https://inflyteapp.com/r/pmqtne)"));
+        }
+        let names: Vec<usize> = messages
+            .iter()
+            .map(|m| m.matches("✨ *DJ ").count())
+            .collect();
+        assert_eq!(names, [3, 3, 3, 1]);
+        assert!(messages[3].contains("✨ *DJ 9*"));
+    }
+}
@@ -0,0 +1,373 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::env;
+use std::time::Duration;
+use tracing::debug;
+
+use super::Notifier;
+use crate::diff::{EventKind, SupportEvent};
+use crate::{Campaign, DjSupport};
+
+/// Version of the payload below; bumped whenever a field changes meaning or goes away
+const PAYLOAD_VERSION: u32 = 1;
+
+/// Header carrying `sha256=<hex HMAC-SHA256 of the body>`
+const SIGNATURE_HEADER: &str = "X-Inflyte-Signature";
+
+/// Header carrying the event ID, the same for every URL and every retry
+const EVENT_ID_HEADER: &str = "X-Inflyte-Event-Id";
+
+/// Header carrying the delivery ID, the same for every retry to one URL
+const DELIVERY_ID_HEADER: &str = "X-Inflyte-Delivery";
+
+/// JSON body posted for every support event
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    version: u32,
+    id: &'a str,
+    delivery_id: &'a str,
+    event: EventKind,
+    campaign: CampaignInfo<'a>,
+    dj: &'a DjSupport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous: Option<&'a DjSupport>,
+    timestamp: &'a str,
+}
+
+#[derive(Serialize)]
+struct CampaignInfo<'a> {
+    name: &'a str,
+    url: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    track_title: Option<String>,
+}
+
+/// POSTs every support event as signed JSON to arbitrary URLs
+///
+/// Each campaign posts to its own `webhook_urls` from the campaign settings, falling back
+/// to `WEBHOOK_URLS`. Every URL is a separate target, so a URL that fails is retried
+/// without posting to the others again; the event and delivery IDs let receivers drop
+/// the repeats a retry may still cause.
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    default_urls: Vec<String>,
+    secret: String,
+}
+
+/// Split a comma-separated list of URLs
+fn parse_urls(urls: &str) -> Vec<String> {
+    urls.split(',')
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .collect()
+}
+
+/// Host of a URL, safe to log where the URL itself may carry a token
+fn host(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "<invalid URL>".to_string())
+}
+
+/// Names a URL as an outbox target without revealing its path or query, e.g.
+/// `hooks.example.com#1f0c2a9e`
+fn target_name(url: &str) -> String {
+    format!("{}#{}", host(url), &stable_id(&[url])[..8])
+}
+
+impl WebhookNotifier {
+    /// Enabled by `WEBHOOK_URLS` or by any campaign with its own URLs; `WEBHOOK_SECRET` is
+    /// then required
+    pub fn from_env(campaigns: &[Campaign]) -> Result<Option<Self>> {
+        let default_urls = env::var("WEBHOOK_URLS")
+            .map(|urls| parse_urls(&urls))
+            .unwrap_or_default();
+        let any_campaign_urls = campaigns
+            .iter()
+            .any(|c| !c.settings.webhook_urls.is_empty());
+        if default_urls.is_empty() && !any_campaign_urls {
+            return Ok(None);
+        }
+
+        let secret = env::var("WEBHOOK_SECRET")
+            .context("WEBHOOK_SECRET must be set when webhooks are configured")?;
+        Ok(Some(WebhookNotifier {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .context("Failed to build webhook HTTP client")?,
+            default_urls,
+            secret,
+        }))
+    }
+
+    fn urls<'a>(&'a self, campaign: &'a Campaign) -> &'a [String] {
+        if campaign.settings.webhook_urls.is_empty() {
+            &self.default_urls
+        } else {
+            &campaign.settings.webhook_urls
+        }
+    }
+
+    /// Hex HMAC-SHA256 of the body, keyed with the shared secret
+    fn sign(&self, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    /// Post one body; anything but a 2xx response is a failure for the outbox to retry
+    async fn deliver(
+        &self,
+        url: &str,
+        kind: EventKind,
+        ids: (&str, &str),
+        body: Vec<u8>,
+    ) -> Result<()> {
+        let (event_id, delivery_id) = ids;
+        let request = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, format!("sha256={}", self.sign(&body)))
+            .header("X-Inflyte-Event", event_name(kind))
+            .header(EVENT_ID_HEADER, event_id)
+            .header(DELIVERY_ID_HEADER, delivery_id)
+            .body(body);
+        let response =
+            super::send(request, &format!("Failed to reach webhook {}", host(url))).await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Webhook {} failed: HTTP {}", host(url), response.status());
+        }
+        Ok(())
+    }
+
+    /// Post every event to one URL, even when some of them fail
+    async fn post_events(
+        &self,
+        campaign: &Campaign,
+        url: &str,
+        events: &[SupportEvent],
+    ) -> Result<()> {
+        let track_title = campaign.metadata.track_title();
+        let mut failures = Vec::new();
+        for event in events {
+            let id = event_id(campaign, event);
+            let delivery_id = stable_id(&[&id, url]);
+            let body = serde_json::to_vec(&WebhookPayload {
+                version: PAYLOAD_VERSION,
+                id: &id,
+                delivery_id: &delivery_id,
+                event: event.kind,
+                campaign: CampaignInfo {
+                    name: &campaign.name,
+                    url: &campaign.url,
+                    track_title: track_title.clone(),
+                },
+                dj: &event.dj,
+                previous: event.previous.as_ref(),
+                timestamp: &event.detected_at,
+            })
+            .context("Failed to serialize webhook payload")?;
+
+            if let Err(e) = self
+                .deliver(url, event.kind, (&id, &delivery_id), body)
+                .await
+            {
+                failures.push(format!("{:#}", e));
+            }
+        }
+
+        if !failures.is_empty() {
+            anyhow::bail!(
+                "{} webhook deliveries failed: {}",
+                failures.len(),
+                failures.join("; ")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Hex SHA-256 of the given parts, shortened to 32 characters
+fn stable_id(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(&hasher.finalize()[..16])
+}
+
+/// ID of an event, derived from what it is about so it survives being stored and retried
+fn event_id(campaign: &Campaign, event: &SupportEvent) -> String {
+    stable_id(&[
+        &campaign.name,
+        &event_name(event.kind),
+        &event.dj.name,
+        &event.detected_at,
+    ])
+}
+
+/// The event kind as named in the payload, e.g. `comment_added`
+fn event_name(kind: EventKind) -> String {
+    serde_json::to_value(kind)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "Webhooks ({} default URL(s) + per-campaign URLs)",
+            self.default_urls.len()
+        )
+    }
+
+    async fn notify(&self, campaign: &Campaign, events: &[SupportEvent]) -> Result<()> {
+        let urls = self.urls(campaign);
+        if urls.is_empty() {
+            debug!(campaign = %campaign.name, "No webhook URLs for campaign, skipping");
+            return Ok(());
+        }
+
+        // Every URL gets every event, even when another URL is failing
+        let mut failures = Vec::new();
+        for url in urls {
+            if let Err(e) = self.post_events(campaign, url, events).await {
+                failures.push(format!("{:#}", e));
+            }
+        }
+
+        if !failures.is_empty() {
+            anyhow::bail!(
+                "{} webhook URL(s) failed: {}",
+                failures.len(),
+                failures.join("; ")
+            );
+        }
+        Ok(())
+    }
+
+    fn targets(&self, campaign: &Campaign) -> Vec<String> {
+        self.urls(campaign)
+            .iter()
+            .map(|url| target_name(url))
+            .collect()
+    }
+
+    async fn notify_target(
+        &self,
+        campaign: &Campaign,
+        target: &str,
+        events: &[SupportEvent],
+    ) -> Result<()> {
+        let url = self
+            .urls(campaign)
+            .iter()
+            .find(|url| target_name(url) == target)
+            .with_context(|| format!("Webhook {} is no longer configured", target))?;
+        self.post_events(campaign, url, events).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{campaign, dj, event, new_support};
+
+    fn notifier(secret: &str) -> WebhookNotifier {
+        WebhookNotifier {
+            client: reqwest::Client::new(),
+            default_urls: Vec::new(),
+            secret: secret.to_string(),
+        }
+    }
+
+    #[test]
+    fn every_url_is_a_target() {
+        let mut notifier = notifier("secret");
+        notifier.default_urls =
+            parse_urls("https://hooks.example.com/in/T0K3N, https://hooks.example.com/in/0TH3R");
+        let mut own = campaign("xk2v9a");
+        own.settings.webhook_urls = vec!["https://example.org/hook?token=s3cr3t".to_string()];
+
+        let targets = notifier.targets(&campaign("pmqtne"));
+        assert_eq!(targets.len(), 2);
+        assert_ne!(targets[0], targets[1]);
+        for target in &targets {
+            assert!(target.starts_with("hooks.example.com#"));
+            assert!(!target.contains("T0K3N") && !target.contains("0TH3R"));
+        }
+
+        let targets = notifier.targets(&own);
+        assert_eq!(targets.len(), 1);
+        assert!(targets[0].starts_with("example.org#"));
+        assert!(!targets[0].contains("s3cr3t"));
+        assert_eq!(
+            targets[0],
+            target_name("https://example.org/hook?token=s3cr3t")
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_name_the_host_only() {
+        let mut notifier = notifier("secret");
+        // Nothing listens on port 1, so the connection is refused right away
+        notifier.default_urls = vec!["http://127.0.0.1:1/in/T0K3N".to_string()];
+        let campaign = campaign("pmqtne");
+        let target = notifier.targets(&campaign).remove(0);
+
+        let error = notifier
+            .notify_target(&campaign, &target, &[new_support("Dixon")])
+            .await
+            .unwrap_err();
+        let error = format!("{:#}", error);
+        assert!(
+            error.contains("Failed to reach webhook 127.0.0.1"),
+            "{}",
+            error
+        );
+        assert!(!error.contains("T0K3N"), "{}", error);
+    }
+
+    #[test]
+    fn signature_matches_known_hmac() {
+        let notifier = notifier("It's a secret to everybody");
+        assert_eq!(
+            notifier.sign(br#"{"version":1}"#),
+            "5013c9163d81e40b53411edd6e11a03db68bcf447ff8317c42c9856bbda3043b"
+        );
+    }
+
+    #[test]
+    fn ids_are_stable() {
+        let campaign = campaign("pmqtne");
+        let event = event(EventKind::New, dj("Miss Kittin", None, Some(5)), None);
+        let id = event_id(&campaign, &event);
+        assert_eq!(id.len(), 32);
+        assert_eq!(id, event_id(&campaign, &event.clone()));
+
+        let rated = SupportEvent {
+            kind: EventKind::RatingChanged,
+            ..event.clone()
+        };
+        assert_ne!(id, event_id(&campaign, &rated));
+        assert_ne!(
+            stable_id(&[&id, "https://a.example.com"]),
+            stable_id(&[&id, "https://b.example.com"])
+        );
+    }
+}
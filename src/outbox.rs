@@ -0,0 +1,769 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
+use tracing::{error, info, warn};
+
+use crate::Campaign;
+use crate::diff::SupportEvent;
+use crate::digest::{DigestPeriod, DigestReport};
+use crate::metadata::CampaignMetadata;
+use crate::notify::Notifiers;
+use crate::storage::DjStore;
+
+/// Longest wait between two attempts, however many have failed
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long the worker sleeps when nothing is waiting to be retried
+const IDLE_WAIT: Duration = Duration::from_secs(60 * 60);
+
+/// Alerts for one campaign on one channel, or one of its targets, waiting to be delivered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub id: String,
+    pub campaign: String,
+    /// Name of the notifier, e.g. `mailgun`
+    pub channel: String,
+    /// Where on the channel the alerts go, e.g. a Telegram chat; the whole channel if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Set for changes waiting for a digest, which is due at the end of the period
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<DigestPeriod>,
+    /// The campaign's metadata when the changes were detected
+    pub metadata: CampaignMetadata,
+    pub events: Vec<SupportEvent>,
+    pub attempts: u32,
+    pub created_at: String,
+    pub next_attempt_at: String,
+    pub last_error: Option<String>,
+    /// Gave up after too many failed attempts; kept for inspection, never retried
+    pub dead: bool,
+}
+
+/// A campaign's alerts for one destination: a channel, or one of its targets
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    pub channel: &'static str,
+    pub target: Option<String>,
+    pub events: Vec<SupportEvent>,
+    /// Held back until then, e.g. for quiet hours; sent right away when unset
+    pub hold_until: Option<DateTime<Utc>>,
+}
+
+/// What one pass over the outbox did
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeliveryReport {
+    pub delivered: usize,
+    /// Failed this time, to be retried later
+    pub failed: usize,
+    /// Given up on this time
+    pub dead_lettered: usize,
+    /// Still waiting afterwards: held for quiet hours or for a retry
+    pub pending: usize,
+    /// When the earliest pending entry is due
+    #[serde(skip)]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// Why the outbox itself couldn't be loaded or saved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl OutboxEntry {
+    fn is_due(&self, now: DateTime<Utc>) -> bool {
+        !self.dead
+            && DateTime::parse_from_rfc3339(&self.next_attempt_at).map_or(true, |at| at <= now)
+    }
+}
+
+/// Alerts written to storage before the DJ list is saved, then delivered by a worker
+///
+/// A failed delivery is retried with exponential backoff, starting at
+/// `OUTBOX_RETRY_SECONDS` and doubling each time. After `OUTBOX_MAX_ATTEMPTS` the entry is
+/// dead-lettered: it stays in the outbox (see `GET /outbox`) but is no longer retried.
+/// Each channel has its own entry, so a failing channel never causes duplicates on the
+/// others.
+#[derive(Debug)]
+pub struct Outbox {
+    max_attempts: u32,
+    initial_delay: Duration,
+    /// Serializes read-modify-write cycles of the stored outbox
+    lock: Mutex<()>,
+    wake: Notify,
+}
+
+impl Outbox {
+    /// Give up after `max_attempts` (at least one), waiting `initial_delay` before the
+    /// first retry
+    pub fn new(max_attempts: u32, initial_delay: Duration) -> Self {
+        Outbox {
+            max_attempts: max_attempts.max(1),
+            initial_delay,
+            lock: Mutex::new(()),
+            wake: Notify::new(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self> {
+        Ok(Outbox::new(
+            env::var("OUTBOX_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .context("OUTBOX_MAX_ATTEMPTS must be a valid number")?,
+            Duration::from_secs(
+                env::var("OUTBOX_RETRY_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .context("OUTBOX_RETRY_SECONDS must be a valid number")?,
+            ),
+        ))
+    }
+
+    /// Log the retry policy at startup
+    pub fn log_summary(&self) {
+        info!(
+            "  Outbox: up to {} attempts, first retry after {}s",
+            self.max_attempts,
+            self.initial_delay.as_secs()
+        );
+    }
+
+    /// How long to wait after the given number of failed attempts
+    fn retry_delay(&self, attempts: u32) -> Duration {
+        let factor = 1u32 << attempts.saturating_sub(1).min(20);
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(MAX_RETRY_DELAY)
+    }
+
+    /// Store a campaign's alerts, one entry per destination, to be sent right away or,
+    /// during quiet hours, at the destination's `hold_until`
+    ///
+    /// Alerts held until the same time are merged, so a night's changes go out as one
+    /// message per destination. Must succeed before the DJ list is saved, otherwise the
+    /// changes would be marked as seen without ever being sent.
+    pub async fn enqueue(
+        &self,
+        store: &dyn DjStore,
+        campaign: &Campaign,
+        deliveries: &[Delivery],
+    ) -> Result<()> {
+        self.store_entries(store, campaign, deliveries, None).await
+    }
+
+    /// Store a campaign's changes for the digest at the end of `period`, one entry per
+    /// destination, due at the end of the period or at the destination's `hold_until` if
+    /// that is later, e.g. when the digest falls in quiet hours
+    ///
+    /// Changes of the same period are merged; once due, every campaign's changes for a
+    /// destination go out as one digest.
+    pub async fn enqueue_digest(
+        &self,
+        store: &dyn DjStore,
+        campaign: &Campaign,
+        deliveries: &[Delivery],
+        period: DigestPeriod,
+    ) -> Result<()> {
+        let until = period.until();
+        let deliveries: Vec<Delivery> = deliveries
+            .iter()
+            .map(|delivery| Delivery {
+                hold_until: Some(delivery.hold_until.map_or(until, |held| held.max(until))),
+                ..delivery.clone()
+            })
+            .collect();
+        self.store_entries(store, campaign, &deliveries, Some(period))
+            .await
+    }
+
+    async fn store_entries(
+        &self,
+        store: &dyn DjStore,
+        campaign: &Campaign,
+        deliveries: &[Delivery],
+        digest: Option<DigestPeriod>,
+    ) -> Result<()> {
+        if deliveries.iter().all(|d| d.events.is_empty()) {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let _guard = self.lock.lock().await;
+        let mut entries = store.load_outbox().await?;
+        for delivery in deliveries.iter().filter(|d| !d.events.is_empty()) {
+            let (channel, target, events) = (delivery.channel, &delivery.target, &delivery.events);
+            let next_attempt_at = delivery.hold_until.unwrap_or(now).to_rfc3339();
+            if delivery.hold_until.is_some()
+                && let Some(held) = entries.iter_mut().find(|e| {
+                    e.campaign == campaign.name
+                        && e.channel == channel
+                        && e.target == *target
+                        && e.digest == digest
+                        && e.attempts == 0
+                        && e.next_attempt_at == next_attempt_at
+                })
+            {
+                held.metadata = campaign.metadata.clone();
+                held.events.extend_from_slice(events);
+                continue;
+            }
+
+            let mut id = format!(
+                "{}-{}-{}",
+                now.format("%Y%m%dT%H%M%S%.3fZ"),
+                campaign.name,
+                channel
+            );
+            if let Some(target) = target {
+                id.push_str(&format!("-{}", target));
+            }
+            if digest.is_some() {
+                id.push_str("-digest");
+            }
+            // Immediate and held alerts of the same check would otherwise share an ID
+            if entries.iter().any(|e| e.id == id) {
+                id.push_str("-held");
+            }
+            entries.push(OutboxEntry {
+                id,
+                campaign: campaign.name.clone(),
+                channel: channel.to_string(),
+                target: target.clone(),
+                digest: digest.clone(),
+                metadata: campaign.metadata.clone(),
+                events: events.clone(),
+                attempts: 0,
+                created_at: now.to_rfc3339(),
+                next_attempt_at,
+                last_error: None,
+                dead: false,
+            });
+        }
+        store
+            .save_outbox(&entries)
+            .await
+            .context("Failed to write alerts to the outbox")
+    }
+
+    /// Have the worker deliver newly enqueued alerts now
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
+    /// Try every entry that is due
+    async fn deliver_due(
+        &self,
+        store: &dyn DjStore,
+        notifiers: &Notifiers,
+        campaigns: &[Campaign],
+    ) -> Result<DeliveryReport> {
+        let now = Utc::now();
+        let due: Vec<OutboxEntry> = {
+            let _guard = self.lock.lock().await;
+            store
+                .load_outbox()
+                .await?
+                .into_iter()
+                .filter(|e| e.is_due(now))
+                .collect()
+        };
+
+        // Deliver without holding the lock so checks can keep enqueueing meanwhile; each
+        // outcome names the entries it covers and how many of their events were sent
+        let mut outcomes = Vec::new();
+        let mut digests: Vec<Vec<(&OutboxEntry, Campaign)>> = Vec::new();
+        for entry in &due {
+            let campaign = match deliverable(entry, notifiers, campaigns) {
+                Ok(campaign) => campaign,
+                Err(e) => {
+                    outcomes.push((vec![(entry.id.clone(), 0)], Err((e, true))));
+                    continue;
+                }
+            };
+            if entry.digest.is_some() {
+                match digests.iter_mut().find(|group| {
+                    group[0].0.channel == entry.channel && group[0].0.target == entry.target
+                }) {
+                    Some(group) => group.push((entry, campaign)),
+                    None => digests.push(vec![(entry, campaign)]),
+                }
+                continue;
+            }
+
+            let result = notifiers
+                .deliver(
+                    &entry.channel,
+                    entry.target.as_deref(),
+                    &campaign,
+                    &entry.events,
+                )
+                .await
+                .map_err(|e| (e, false));
+            outcomes.push((vec![(entry.id.clone(), entry.events.len())], result));
+        }
+
+        // Every campaign's digest changes due on a channel go out as one digest
+        for group in digests {
+            let report = DigestReport::collect(group.iter().map(|(entry, campaign)| {
+                (
+                    entry
+                        .digest
+                        .as_ref()
+                        .expect("only digest entries are grouped"),
+                    campaign.clone(),
+                    entry.events.as_slice(),
+                )
+            }));
+            let (first, _) = &group[0];
+            info!(
+                notifier = %first.channel,
+                target = ?first.target,
+                campaigns = report.entries.len(),
+                events = report.event_count(),
+                "Sending digest"
+            );
+            let result = notifiers
+                .deliver_digest(&first.channel, first.target.as_deref(), &report)
+                .await
+                .map_err(|e| (e, false));
+            let ids = group
+                .iter()
+                .map(|(entry, _)| (entry.id.clone(), entry.events.len()))
+                .collect();
+            outcomes.push((ids, result));
+        }
+
+        let _guard = self.lock.lock().await;
+        let mut entries = store.load_outbox().await?;
+        let mut report = DeliveryReport::default();
+        for (ids, result) in outcomes {
+            // With causes, e.g. why the SMTP connection failed
+            let result = result.map_err(|(e, permanent)| (format!("{:#}", e), permanent));
+            for (id, sent) in ids {
+                let Some(index) = entries.iter().position(|e| e.id == id) else {
+                    continue;
+                };
+                let entry = &mut entries[index];
+                match &result {
+                    Ok(()) => {
+                        info!(
+                            campaign = %entry.campaign,
+                            notifier = %entry.channel,
+                            target = ?entry.target,
+                            "Notification sent"
+                        );
+                        // Changes merged into a held entry while it was being sent go out
+                        // with the next attempt
+                        if entry.events.len() > sent {
+                            entry.events.drain(..sent);
+                        } else {
+                            entries.remove(index);
+                        }
+                        report.delivered += 1;
+                    }
+                    Err((error, permanent)) => {
+                        entry.attempts += 1;
+                        entry.last_error = Some(error.clone());
+                        if *permanent || entry.attempts >= self.max_attempts {
+                            entry.dead = true;
+                            report.dead_lettered += 1;
+                            error!(
+                                campaign = %entry.campaign,
+                                notifier = %entry.channel,
+                                target = ?entry.target,
+                                attempts = entry.attempts,
+                                error,
+                                "Giving up on notification, moved to dead letters"
+                            );
+                        } else {
+                            report.failed += 1;
+                            let delay = self.retry_delay(entry.attempts);
+                            entry.next_attempt_at =
+                                (Utc::now() + chrono::Duration::from_std(delay)?).to_rfc3339();
+                            warn!(
+                                campaign = %entry.campaign,
+                                notifier = %entry.channel,
+                                target = ?entry.target,
+                                attempt = entry.attempts,
+                                error,
+                                retry_in = ?delay,
+                                "Failed to send notification, will retry"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        store.save_outbox(&entries).await?;
+
+        let pending: Vec<&OutboxEntry> = entries.iter().filter(|e| !e.dead).collect();
+        report.pending = pending.len();
+        report.next_attempt_at = pending
+            .iter()
+            .filter_map(|e| DateTime::parse_from_rfc3339(&e.next_attempt_at).ok())
+            .map(|at| at.with_timezone(&Utc))
+            .min();
+        Ok(report)
+    }
+
+    /// Deliver every alert that is due now, once
+    pub async fn flush(
+        &self,
+        store: &dyn DjStore,
+        notifiers: &Notifiers,
+        campaigns: &[Campaign],
+    ) -> Result<DeliveryReport> {
+        self.deliver_due(store, notifiers, campaigns).await
+    }
+
+    /// Deliver alerts as they are enqueued and retry failed ones, forever
+    ///
+    /// Anything left over from a previous run is delivered right away.
+    pub async fn run(
+        &self,
+        store: Arc<dyn DjStore>,
+        notifiers: Notifiers,
+        campaigns: Vec<Campaign>,
+    ) {
+        loop {
+            let wait = match self
+                .deliver_due(store.as_ref(), &notifiers, &campaigns)
+                .await
+            {
+                Ok(DeliveryReport {
+                    next_attempt_at: Some(next),
+                    ..
+                }) => (next - Utc::now()).to_std().unwrap_or_default(),
+                Ok(_) => IDLE_WAIT,
+                Err(e) => {
+                    error!(error = %e, "Failed to process the outbox");
+                    self.initial_delay.max(Duration::from_secs(1))
+                }
+            };
+            tokio::select! {
+                _ = self.wake.notified() => {}
+                _ = tokio::time::sleep(wait) => {}
+            }
+        }
+    }
+}
+
+/// The campaign to deliver an entry for, with its metadata as of the entry, or why the
+/// entry can no longer be delivered
+fn deliverable(
+    entry: &OutboxEntry,
+    notifiers: &Notifiers,
+    campaigns: &[Campaign],
+) -> Result<Campaign> {
+    let campaign = campaigns
+        .iter()
+        .find(|c| c.name == entry.campaign)
+        .context("Campaign is no longer monitored")?;
+    if !notifiers.routed(campaign).contains(&entry.channel.as_str()) {
+        anyhow::bail!("Notifier is no longer enabled for this campaign");
+    }
+    if let Some(target) = &entry.target
+        && !notifiers.targets(&entry.channel, campaign).contains(target)
+    {
+        anyhow::bail!("Target is no longer configured for this campaign");
+    }
+    Ok(Campaign {
+        metadata: entry.metadata.clone(),
+        ..campaign.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::names::NameNormalizer;
+    use crate::notify::Notifier;
+    use crate::storage::{RetentionPolicy, SqliteStore};
+    use crate::testing::{campaign, new_support as event};
+    use async_trait::async_trait;
+    use std::path::Path;
+
+    fn outbox() -> Outbox {
+        Outbox::new(10, Duration::from_secs(60))
+    }
+
+    fn open_store() -> SqliteStore {
+        SqliteStore::open(
+            Path::new(":memory:"),
+            RetentionPolicy::default(),
+            NameNormalizer::default(),
+        )
+        .unwrap()
+    }
+
+    /// Delivers to the chats `a` and `b`, of which `b` is down, recording the digests sent
+    #[derive(Default)]
+    struct Chats {
+        digests: std::sync::Mutex<Vec<(String, Vec<String>)>>,
+    }
+
+    #[async_trait]
+    impl Notifier for Chats {
+        fn name(&self) -> &'static str {
+            "chats"
+        }
+
+        fn describe(&self) -> String {
+            "Test chats".to_string()
+        }
+
+        async fn notify(&self, _campaign: &Campaign, _events: &[SupportEvent]) -> Result<()> {
+            anyhow::bail!("Alerts go to each chat separately")
+        }
+
+        fn targets(&self, _campaign: &Campaign) -> Vec<String> {
+            vec!["a".to_string(), "b".to_string()]
+        }
+
+        async fn notify_target(
+            &self,
+            _campaign: &Campaign,
+            target: &str,
+            _events: &[SupportEvent],
+        ) -> Result<()> {
+            match target {
+                "b" => anyhow::bail!("Chat b is down"),
+                _ => Ok(()),
+            }
+        }
+
+        async fn notify_digest_target(&self, report: &DigestReport, target: &str) -> Result<()> {
+            if target == "b" {
+                anyhow::bail!("Chat b is down");
+            }
+            let campaigns = report
+                .entries
+                .iter()
+                .map(|e| e.campaign.name.clone())
+                .collect();
+            self.digests
+                .lock()
+                .unwrap()
+                .push((target.to_string(), campaigns));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_targets_are_retried_alone() {
+        let store = open_store();
+        let mut notifiers = Notifiers::default();
+        notifiers.push(Arc::new(Chats::default()));
+        let campaign = campaign("pmqtne");
+
+        let outbox = outbox();
+        let deliveries: Vec<Delivery> = notifiers
+            .destinations(&campaign)
+            .into_iter()
+            .map(|(channel, target)| Delivery {
+                channel,
+                target,
+                events: vec![event("Kölsch")],
+                hold_until: None,
+            })
+            .collect();
+        outbox
+            .enqueue(&store, &campaign, &deliveries)
+            .await
+            .unwrap();
+        let targets: Vec<Option<String>> = store
+            .load_outbox()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.target)
+            .collect();
+        assert_eq!(targets, [Some("a".to_string()), Some("b".to_string())]);
+
+        let report = outbox
+            .flush(&store, &notifiers, std::slice::from_ref(&campaign))
+            .await
+            .unwrap();
+        assert_eq!((report.delivered, report.failed), (1, 1));
+        let entries = store.load_outbox().await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].target.as_deref(), Some("b"));
+        assert_eq!(entries[0].attempts, 1);
+    }
+
+    /// Records the DJs of every alert sent; while sending the first, the check enqueues
+    /// another change held until the same time
+    struct Racing {
+        outbox: Arc<Outbox>,
+        store: Arc<SqliteStore>,
+        hold_until: DateTime<Utc>,
+        sent: std::sync::Mutex<Vec<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Notifier for Racing {
+        fn name(&self) -> &'static str {
+            "racing"
+        }
+
+        fn describe(&self) -> String {
+            "Test racing check".to_string()
+        }
+
+        async fn notify(&self, campaign: &Campaign, events: &[SupportEvent]) -> Result<()> {
+            let first = self.sent.lock().unwrap().is_empty();
+            if first {
+                let late = Delivery {
+                    channel: "racing",
+                    target: None,
+                    events: vec![event("Dixon")],
+                    hold_until: Some(self.hold_until),
+                };
+                self.outbox
+                    .enqueue(self.store.as_ref(), campaign, &[late])
+                    .await?;
+            }
+            let djs = events.iter().map(|e| e.dj.name.clone()).collect();
+            self.sent.lock().unwrap().push(djs);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn changes_merged_during_delivery_are_kept() {
+        let store = Arc::new(open_store());
+        let outbox = Arc::new(outbox());
+        let campaign = campaign("pmqtne");
+        let hold_until = Utc::now() - chrono::Duration::minutes(1);
+        let racing = Arc::new(Racing {
+            outbox: outbox.clone(),
+            store: store.clone(),
+            hold_until,
+            sent: Default::default(),
+        });
+        let mut notifiers = Notifiers::default();
+        notifiers.push(racing.clone());
+
+        let held = Delivery {
+            channel: "racing",
+            target: None,
+            events: vec![event("Kölsch")],
+            hold_until: Some(hold_until),
+        };
+        outbox
+            .enqueue(store.as_ref(), &campaign, &[held])
+            .await
+            .unwrap();
+
+        let campaigns = std::slice::from_ref(&campaign);
+        let report = outbox
+            .flush(store.as_ref(), &notifiers, campaigns)
+            .await
+            .unwrap();
+        assert_eq!((report.delivered, report.pending), (1, 1));
+        let entries = store.load_outbox().await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].events.len(), 1);
+        assert_eq!(entries[0].events[0].dj.name, "Dixon");
+
+        outbox
+            .flush(store.as_ref(), &notifiers, campaigns)
+            .await
+            .unwrap();
+        assert!(store.load_outbox().await.unwrap().is_empty());
+        assert_eq!(
+            *racing.sent.lock().unwrap(),
+            [vec!["Kölsch".to_string()], vec!["Dixon".to_string()]]
+        );
+    }
+
+    #[tokio::test]
+    async fn digests_are_stored_until_due() {
+        let store = open_store();
+        let chats = Arc::new(Chats::default());
+        let mut notifiers = Notifiers::default();
+        notifiers.push(chats.clone());
+        let campaigns = [campaign("pmqtne"), campaign("xk2v9a")];
+        let outbox = outbox();
+
+        let hour = chrono::Duration::hours(1);
+        let due = DigestPeriod {
+            since: (Utc::now() - hour * 2).to_rfc3339(),
+            until: (Utc::now() - hour).to_rfc3339(),
+        };
+        let later = DigestPeriod {
+            since: (Utc::now() - hour).to_rfc3339(),
+            until: (Utc::now() + hour).to_rfc3339(),
+        };
+        let deliveries = |campaign: &Campaign, dj: &str| -> Vec<Delivery> {
+            notifiers
+                .destinations(campaign)
+                .into_iter()
+                .map(|(channel, target)| Delivery {
+                    channel,
+                    target,
+                    events: vec![event(dj)],
+                    hold_until: None,
+                })
+                .collect()
+        };
+        for (campaign, dj) in campaigns.iter().zip(["Kölsch", "Miss Kittin"]) {
+            outbox
+                .enqueue_digest(&store, campaign, &deliveries(campaign, dj), due.clone())
+                .await
+                .unwrap();
+        }
+        outbox
+            .enqueue_digest(
+                &store,
+                &campaigns[0],
+                &deliveries(&campaigns[0], "Tale Of Us"),
+                due.clone(),
+            )
+            .await
+            .unwrap();
+        outbox
+            .enqueue_digest(
+                &store,
+                &campaigns[0],
+                &deliveries(&campaigns[0], "Âme"),
+                later,
+            )
+            .await
+            .unwrap();
+        // Changes of the same period are merged, per campaign and chat
+        assert_eq!(store.load_outbox().await.unwrap().len(), 6);
+
+        let report = outbox.flush(&store, &notifiers, &campaigns).await.unwrap();
+        assert_eq!((report.delivered, report.failed, report.pending), (2, 2, 4));
+        // One digest of both campaigns; the failed chat keeps its entries for a retry
+        assert_eq!(
+            *chats.digests.lock().unwrap(),
+            [(
+                "a".to_string(),
+                vec!["pmqtne".to_string(), "xk2v9a".to_string()]
+            )]
+        );
+        let failed: Vec<OutboxEntry> = store
+            .load_outbox()
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.attempts == 1)
+            .collect();
+        assert_eq!(failed.len(), 2);
+        assert!(failed.iter().all(|e| e.target.as_deref() == Some("b")));
+    }
+
+    #[test]
+    fn retry_backoff() {
+        let outbox = outbox();
+        assert_eq!(outbox.retry_delay(1), Duration::from_secs(60));
+        assert_eq!(outbox.retry_delay(2), Duration::from_secs(120));
+        assert_eq!(outbox.retry_delay(5), Duration::from_secs(960));
+        assert_eq!(outbox.retry_delay(40), MAX_RETRY_DELAY);
+    }
+}
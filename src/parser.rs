@@ -0,0 +1,347 @@
+use anyhow::{Context, Result};
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::DjSupport;
+use crate::names::NameNormalizer;
+
+/// How DJs are located on a campaign page
+///
+/// The defaults match the current inflyteapp.com layout. Any field left out of a rules
+/// file keeps its default, so a file only needs to list what differs:
+///
+/// ```json
+/// { "section_heading": "Unterstützung", "list_marker": "Unterstützt von" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExtractionRules {
+    /// Selector of the heading that opens the Support section (and of the next heading,
+    /// which closes it)
+    pub heading_selector: String,
+    /// Text of the heading that opens the Support section
+    pub section_heading: String,
+    /// Selector of the element holding a single DJ's profile card
+    pub card_selector: String,
+    /// Selector of the profile picture that identifies a card
+    pub image_selector: String,
+    /// Selector of the DJ name within a card; defaults to the card's first line of text
+    pub name_selector: Option<String>,
+    /// Selector of the comment within a card; defaults to the lines after the name
+    pub comment_selector: Option<String>,
+    /// Selector of one rating star within a card; defaults to counting `star_symbol`
+    pub star_selector: Option<String>,
+    /// Character shown once per rating star
+    pub star_symbol: char,
+    /// Text introducing the list of DJs without a profile card
+    pub list_marker: String,
+    /// Words joining the last two names of the list ("A, B and C")
+    pub list_conjunctions: Vec<String>,
+    /// Text following the list that must not be taken for DJ names
+    pub stop_phrases: Vec<String>,
+}
+
+impl Default for ExtractionRules {
+    fn default() -> Self {
+        ExtractionRules {
+            heading_selector: "h3".to_string(),
+            section_heading: "Support".to_string(),
+            card_selector: "div".to_string(),
+            image_selector: "img".to_string(),
+            name_selector: None,
+            comment_selector: None,
+            star_selector: None,
+            star_symbol: '⭐',
+            list_marker: "Support from".to_string(),
+            list_conjunctions: vec!["and".to_string()],
+            stop_phrases: vec!["Get Mad".to_string(), "Currently subscribed".to_string()],
+        }
+    }
+}
+
+impl ExtractionRules {
+    /// Load extraction rules from a JSON file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read extraction rules: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse extraction rules: {}", path.display()))
+    }
+}
+
+/// What the parser saw in the Support section, used to judge whether the page layout
+/// still matches what the parser expects
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ParseStats {
+    /// Whether the Support section heading was found
+    pub heading_found: bool,
+    /// Pictures in the Support section (one per profile card)
+    pub profile_images: usize,
+    /// Profile cards a DJ was extracted from
+    pub profile_cards: usize,
+    /// Whether a "Support from" list was found
+    pub list_found: bool,
+    /// Names extracted from the "Support from" list
+    pub list_names: usize,
+}
+
+/// The DJs parsed from a campaign page, along with parse-quality signals
+#[derive(Debug, Clone, Default)]
+pub struct SupportSection {
+    pub djs: HashSet<DjSupport>,
+    pub stats: ParseStats,
+}
+
+/// Parses the Support section of campaign pages according to a set of [`ExtractionRules`]
+#[derive(Debug, Clone)]
+pub struct SupportParser {
+    rules: ExtractionRules,
+    heading: Selector,
+    card: Selector,
+    image: Selector,
+    name: Option<Selector>,
+    comment: Option<Selector>,
+    star: Option<Selector>,
+}
+
+/// Parse a CSS selector from the extraction rules
+fn selector(field: &str, css: &str) -> Result<Selector> {
+    Selector::parse(css).map_err(|e| anyhow::anyhow!("Invalid {} selector '{}': {}", field, css, e))
+}
+
+impl SupportParser {
+    /// Compile the rules' selectors, failing on invalid CSS
+    pub fn new(rules: ExtractionRules) -> Result<Self> {
+        let optional = |field: &str, css: &Option<String>| {
+            css.as_deref().map(|css| selector(field, css)).transpose()
+        };
+
+        Ok(SupportParser {
+            heading: selector("heading", &rules.heading_selector)?,
+            card: selector("card", &rules.card_selector)?,
+            image: selector("image", &rules.image_selector)?,
+            name: optional("name", &rules.name_selector)?,
+            comment: optional("comment", &rules.comment_selector)?,
+            star: optional("star", &rules.star_selector)?,
+            rules,
+        })
+    }
+
+    pub fn rules(&self) -> &ExtractionRules {
+        &self.rules
+    }
+
+    /// Parse DJ names, comments and star ratings from the Support section of a campaign page
+    ///
+    /// The Support section starts at the section heading and runs until the next heading.
+    /// DJs appear either as profile cards (an image followed by the name, stars and
+    /// comment) or as names in a trailing "Support from A, B and C" list. Names are
+    /// canonicalized and duplicates merged with `names`.
+    pub fn parse(&self, html: &str, names: &NameNormalizer) -> SupportSection {
+        let document = Html::parse_document(html);
+
+        let mut djs = HashSet::new();
+        let mut stats = ParseStats::default();
+
+        let Some(heading) = document
+            .select(&self.heading)
+            .find(|h| h.text().collect::<String>().trim() == self.rules.section_heading)
+        else {
+            return SupportSection { djs, stats };
+        };
+        stats.heading_found = true;
+
+        for sibling in heading.next_siblings() {
+            let Some(elem) = ElementRef::wrap(sibling) else {
+                continue;
+            };
+            // The section ends at the next heading
+            if self.heading.matches(&elem) {
+                break;
+            }
+
+            // Profile cards contain the DJ's picture
+            let images = elem.select(&self.image).count();
+            if images > 0 {
+                stats.profile_images += images;
+
+                // Each card is the innermost card element holding a picture; parent
+                // containers wrapping several cards are skipped
+                let cards: Vec<ElementRef> = elem
+                    .select(&self.card)
+                    .filter(|card| card.select(&self.image).next().is_some())
+                    .filter(|card| {
+                        !card
+                            .select(&self.card)
+                            .any(|inner| inner.select(&self.image).next().is_some())
+                    })
+                    .collect();
+
+                let parsed: Vec<DjSupport> = if cards.is_empty() {
+                    // No nested cards: the element itself is a single card, which must
+                    // have at least a name and a comment line
+                    self.parse_profile_card(elem, 2).into_iter().collect()
+                } else {
+                    cards
+                        .into_iter()
+                        .filter_map(|card| self.parse_profile_card(card, 1))
+                        .collect()
+                };
+                stats.profile_cards += parsed.len();
+                djs.extend(parsed);
+            }
+
+            let text = elem.text().collect::<String>();
+            if let Some(after_marker) = text.split(self.rules.list_marker.as_str()).nth(1) {
+                let list = self.parse_support_list(after_marker);
+                stats.list_found = true;
+                stats.list_names += list.len();
+
+                for name in list {
+                    // Only add if the DJ isn't already listed under any spelling
+                    let identity = names.identity(&name);
+                    if !djs.iter().any(|dj| names.identity(&dj.name) == identity) {
+                        djs.insert(DjSupport {
+                            name,
+                            comment: None,
+                            stars: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        // Canonicalize names and merge spelling variants of the same DJ
+        SupportSection {
+            djs: names.merge(djs),
+            stats,
+        }
+    }
+
+    /// Parse a profile card: unless selectors are configured, the first line holds the
+    /// name (followed by one star symbol per star) and the remaining lines, up to any
+    /// "Support from" list, form the comment
+    fn parse_profile_card(&self, card: ElementRef, min_lines: usize) -> Option<DjSupport> {
+        let full_text = card.text().collect::<String>();
+        let lines: Vec<&str> = full_text
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .collect();
+
+        if lines.len() < min_lines {
+            return None;
+        }
+
+        let name_line = match &self.name {
+            Some(selector) => card.select(selector).next()?.text().collect::<String>(),
+            None => lines[0].to_string(),
+        };
+        let name = name_line.split(self.rules.star_symbol).next()?.trim();
+        if name.is_empty() || name.len() >= 100 {
+            return None;
+        }
+
+        let comment = match &self.comment {
+            Some(selector) => card
+                .select(selector)
+                .map(|c| c.text().collect::<String>().trim().to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+            None => lines[1..]
+                .iter()
+                .take_while(|line| !line.starts_with(&self.rules.list_marker))
+                .copied()
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+
+        let stars = match &self.star {
+            Some(selector) => card.select(selector).count(),
+            None => full_text.matches(self.rules.star_symbol).count(),
+        };
+        // A selector that matches too broadly must not wrap around to a low rating
+        let stars = u8::try_from(stars).unwrap_or(u8::MAX);
+
+        Some(DjSupport {
+            name: name.to_string(),
+            comment: Some(comment.trim().to_string()).filter(|c| !c.is_empty()),
+            stars: Some(stars).filter(|s| *s > 0),
+        })
+    }
+
+    /// Split the text after the list marker into DJ names ("A, B and C")
+    ///
+    /// Line breaks also end a name, so text following the list in a later paragraph is
+    /// dropped by the stop phrases instead of being glued onto the last DJ.
+    fn parse_support_list(&self, text: &str) -> Vec<String> {
+        let mut text = text.to_string();
+        for conjunction in &self.rules.list_conjunctions {
+            text = text.replace(&format!(" {} ", conjunction), ", ");
+        }
+
+        text.split([',', '\n'])
+            .map(|s| s.trim())
+            .filter(|s| {
+                !s.is_empty()
+                    && s.len() < 100
+                    && !self
+                        .rules
+                        .stop_phrases
+                        .iter()
+                        .any(|phrase| s.starts_with(phrase.as_str()))
+            })
+            .map(|s| s.to_string())
+            .collect()
+    }
+}
+
+impl Default for SupportParser {
+    fn default() -> Self {
+        SupportParser::new(ExtractionRules::default()).expect("default extraction rules are valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What a fixture snapshot records: the parse-quality signals and the sorted DJs
+    #[derive(Serialize)]
+    struct ParsedPage {
+        stats: ParseStats,
+        djs: Vec<DjSupport>,
+    }
+
+    /// Every page saved under `tests/fixtures` is parsed and compared against its snapshot
+    /// in `tests/snapshots`. A page `foo.html` is parsed with the rules in `foo.rules.json`
+    /// when that file exists, and with the default rules otherwise.
+    #[test]
+    fn support_section_fixtures() {
+        insta::with_settings!({
+            snapshot_path => "../tests/snapshots",
+            prepend_module_to_snapshot => false,
+        }, {
+            insta::glob!("../tests/fixtures", "*.html", |path| {
+                let html = std::fs::read_to_string(path).unwrap();
+                let rules_path = path.with_extension("rules.json");
+                let parser = if rules_path.exists() {
+                    SupportParser::new(ExtractionRules::from_file(&rules_path).unwrap()).unwrap()
+                } else {
+                    SupportParser::default()
+                };
+
+                let section = parser.parse(&html, &NameNormalizer::default());
+                let mut djs: Vec<DjSupport> = section.djs.into_iter().collect();
+                djs.sort_by(|a, b| a.name.cmp(&b.name));
+                insta::assert_json_snapshot!(ParsedPage {
+                    stats: section.stats,
+                    djs,
+                });
+            });
+        });
+    }
+}
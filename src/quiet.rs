@@ -0,0 +1,153 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+/// A daily window, in local time, during which alerts are held back
+///
+/// Written as `22:00-08:00`; windows may wrap past midnight. `off` is a window that never
+/// applies, so a campaign can opt out of the global quiet hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct QuietHours {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Global quiet hours from `QUIET_HOURS`; none unless set
+    pub fn from_env() -> Result<Option<Self>> {
+        env::var("QUIET_HOURS")
+            .ok()
+            .map(|hours| hours.parse())
+            .transpose()
+            .context("Invalid QUIET_HOURS")
+    }
+
+    /// When alerts detected at `now` may go out, or `None` if that is right away
+    pub fn held_until(&self, now: DateTime<Utc>, tz: Tz) -> Option<DateTime<Utc>> {
+        let local = now.with_timezone(&tz);
+        let time = local.time();
+        let quiet = match self.start.cmp(&self.end) {
+            std::cmp::Ordering::Equal => false,
+            std::cmp::Ordering::Less => self.start <= time && time < self.end,
+            std::cmp::Ordering::Greater => time >= self.start || time < self.end,
+        };
+        if !quiet {
+            return None;
+        }
+
+        // Past midnight already, the window ends today; before midnight, tomorrow
+        let date = match time < self.end {
+            true => local.date_naive(),
+            false => local.date_naive() + Duration::days(1),
+        };
+        Some(local_to_utc(tz, date.and_time(self.end)))
+    }
+}
+
+/// A local wall-clock time as an instant; a time skipped when clocks go forward is moved
+/// an hour later
+pub fn local_to_utc(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+        })
+        .expect("a local time an hour after a DST gap exists")
+        .with_timezone(&Utc)
+}
+
+impl FromStr for QuietHours {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("off") {
+            return Ok(QuietHours {
+                start: NaiveTime::MIN,
+                end: NaiveTime::MIN,
+            });
+        }
+
+        let (start, end) = s
+            .split_once('-')
+            .with_context(|| format!("Quiet hours '{}' must look like 22:00-08:00", s))?;
+        let time = |t: &str| {
+            NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                .with_context(|| format!("Invalid time '{}' in quiet hours (expected HH:MM)", t))
+        };
+        Ok(QuietHours {
+            start: time(start)?,
+            end: time(end)?,
+        })
+    }
+}
+
+impl TryFrom<String> for QuietHours {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            return write!(f, "off");
+        }
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+/// The timezone quiet hours and digest times are given in, from `TIMEZONE` (default UTC)
+pub fn timezone_from_env() -> Result<Tz> {
+    match env::var("TIMEZONE") {
+        Ok(name) => name.parse().map_err(|_| {
+            anyhow::anyhow!("Invalid TIMEZONE '{}': expected e.g. Europe/Berlin", name)
+        }),
+        Err(_) => Ok(Tz::UTC),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quiet_hours_hold_until() {
+        let berlin: Tz = "Europe/Berlin".parse().unwrap();
+        let night: QuietHours = "22:00-08:00".parse().unwrap();
+        let at = |d, h, m| Utc.with_ymd_and_hms(2025, 1, d, h, m, 0).unwrap();
+
+        // Berlin is UTC+1 in January
+        assert_eq!(night.held_until(at(15, 12, 0), berlin), None);
+        assert_eq!(night.held_until(at(15, 20, 59), berlin), None);
+        assert_eq!(night.held_until(at(15, 21, 0), berlin), Some(at(16, 7, 0)));
+        assert_eq!(night.held_until(at(16, 2, 30), berlin), Some(at(16, 7, 0)));
+        assert_eq!(night.held_until(at(16, 7, 0), berlin), None);
+        assert_eq!(night.held_until(at(15, 23, 0), Tz::UTC), Some(at(16, 8, 0)));
+
+        let lunch: QuietHours = "12:00-13:30".parse().unwrap();
+        assert_eq!(
+            lunch.held_until(at(15, 12, 15), Tz::UTC),
+            Some(at(15, 13, 30))
+        );
+        assert_eq!(lunch.held_until(at(15, 13, 30), Tz::UTC), None);
+
+        let off: QuietHours = "off".parse().unwrap();
+        assert_eq!(off.held_until(at(15, 23, 0), Tz::UTC), None);
+        assert!("22:00".parse::<QuietHours>().is_err());
+        assert!("22:00-25:00".parse::<QuietHours>().is_err());
+    }
+}
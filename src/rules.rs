@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+
+use crate::diff::SupportEvent;
+use crate::names::NameNormalizer;
+
+/// How the star and comment conditions of [`AlertRules`] combine
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleMatch {
+    /// Every configured condition must hold
+    #[default]
+    All,
+    /// One configured condition is enough
+    Any,
+}
+
+/// Which support changes are worth an alert
+///
+/// Events that don't match are still recorded in the event log, they just aren't sent.
+/// VIP DJs always alert; otherwise the configured conditions (minimum stars, comment
+/// present) are combined according to `match`. With no condition, every event alerts.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertRules {
+    /// Only alert for DJs rating the track at least this many stars
+    pub min_stars: Option<u8>,
+    /// Only alert for DJs who left a comment
+    pub require_comment: Option<bool>,
+    #[serde(rename = "match")]
+    pub match_mode: Option<RuleMatch>,
+    /// DJs who always alert, whatever the other rules say
+    pub vip_djs: Vec<String>,
+}
+
+impl AlertRules {
+    /// Global rules from `ALERT_MIN_STARS`, `ALERT_REQUIRE_COMMENT`, `ALERT_RULES_MATCH`
+    /// and `VIP_DJS`
+    pub fn from_env() -> Result<Self> {
+        Ok(AlertRules {
+            min_stars: env::var("ALERT_MIN_STARS")
+                .ok()
+                .map(|s| s.parse())
+                .transpose()
+                .context("ALERT_MIN_STARS must be a number")?,
+            require_comment: env::var("ALERT_REQUIRE_COMMENT")
+                .ok()
+                .map(|s| s.parse())
+                .transpose()
+                .context("ALERT_REQUIRE_COMMENT must be true or false")?,
+            match_mode: env::var("ALERT_RULES_MATCH")
+                .ok()
+                .map(|s| match s.to_lowercase().as_str() {
+                    "all" => Ok(RuleMatch::All),
+                    "any" => Ok(RuleMatch::Any),
+                    other => Err(anyhow::anyhow!(
+                        "Invalid ALERT_RULES_MATCH '{}': expected all or any",
+                        other
+                    )),
+                })
+                .transpose()?,
+            vip_djs: env::var("VIP_DJS")
+                .map(|names| {
+                    names
+                        .split(',')
+                        .map(|name| name.trim().to_string())
+                        .filter(|name| !name.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+
+    /// These rules with a campaign's own rules applied on top: the campaign's conditions
+    /// replace the global ones, and its VIPs are added to the global watchlist
+    pub fn with_overrides(&self, campaign: &AlertRules) -> AlertRules {
+        AlertRules {
+            min_stars: campaign.min_stars.or(self.min_stars),
+            require_comment: campaign.require_comment.or(self.require_comment),
+            match_mode: campaign.match_mode.or(self.match_mode),
+            vip_djs: self
+                .vip_djs
+                .iter()
+                .chain(&campaign.vip_djs)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Whether the event's DJ is on the VIP watchlist
+    pub fn is_vip(&self, event: &SupportEvent, names: &NameNormalizer) -> bool {
+        let identity = names.identity(&event.dj.name);
+        self.vip_djs
+            .iter()
+            .any(|vip| names.identity(vip) == identity)
+    }
+
+    /// Whether the event should be alerted on
+    pub fn matches(&self, event: &SupportEvent, names: &NameNormalizer) -> bool {
+        if self.is_vip(event, names) {
+            return true;
+        }
+        let dj = &event.dj;
+
+        let conditions: Vec<bool> = [
+            self.min_stars
+                .map(|min| dj.stars.is_some_and(|stars| stars >= min)),
+            self.require_comment
+                .filter(|required| *required)
+                .map(|_| dj.comment.is_some()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        if conditions.is_empty() {
+            return true;
+        }
+        match self.match_mode.unwrap_or_default() {
+            RuleMatch::All => conditions.iter().all(|c| *c),
+            RuleMatch::Any => conditions.iter().any(|c| *c),
+        }
+    }
+}
+
+impl fmt::Display for AlertRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut conditions = Vec::new();
+        if let Some(min) = self.min_stars {
+            conditions.push(format!("{}+ stars", min));
+        }
+        if self.require_comment == Some(true) {
+            conditions.push("a comment".to_string());
+        }
+
+        let joiner = match self.match_mode.unwrap_or_default() {
+            RuleMatch::All => " and ",
+            RuleMatch::Any => " or ",
+        };
+        match conditions.is_empty() {
+            true => write!(f, "every change")?,
+            false => write!(f, "{}", conditions.join(joiner))?,
+        }
+        if !self.vip_djs.is_empty() {
+            write!(f, " (plus {} VIP DJs)", self.vip_djs.len())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::EventKind;
+    use crate::testing::{dj, event};
+
+    fn new_support(name: &str, comment: Option<&str>, stars: Option<u8>) -> SupportEvent {
+        event(EventKind::New, dj(name, comment, stars), None)
+    }
+
+    #[test]
+    fn alert_rule_matching() {
+        let names = NameNormalizer::default();
+        let listed = new_support("Kölsch", None, None);
+        let rated = new_support("Dixon", None, Some(4));
+        let commented = new_support("Maceo Plex", Some("Nice"), Some(2));
+        let vip = new_support("DJ Tennis", None, None);
+
+        let everything = AlertRules::default();
+        assert!(everything.matches(&listed, &names));
+
+        let global = AlertRules {
+            min_stars: Some(4),
+            require_comment: Some(true),
+            vip_djs: vec!["Tennis".to_string()],
+            ..Default::default()
+        };
+        assert!(!global.matches(&listed, &names));
+        assert!(!global.matches(&rated, &names));
+        assert!(!global.matches(&commented, &names));
+        // Matched by identity, so name variants of a VIP count too
+        assert!(global.matches(&vip, &names));
+
+        let any = global.with_overrides(&AlertRules {
+            match_mode: Some(RuleMatch::Any),
+            vip_djs: vec!["Kölsch".to_string()],
+            ..Default::default()
+        });
+        assert!(any.matches(&rated, &names));
+        assert!(any.matches(&commented, &names));
+        assert!(any.matches(&listed, &names));
+        assert!(any.matches(&vip, &names));
+    }
+}
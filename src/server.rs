@@ -0,0 +1,232 @@
+use anyhow::Result;
+use axum::{
+    Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::get,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tower_http::cors::CorsLayer;
+use tracing::info;
+
+use crate::health::HealthIssue;
+use crate::metadata::CampaignMetadata;
+use crate::storage::DjStore;
+use crate::{Campaign, Config};
+
+/// Shared application state for HTTP server
+#[derive(Clone)]
+pub struct AppState {
+    pub config: Arc<Config>,
+    pub store: Arc<dyn DjStore>,
+    pub campaign_stats: Arc<RwLock<Vec<CampaignStats>>>,
+}
+
+/// Campaign statistics for HTTP endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct CampaignStats {
+    pub name: String,
+    pub url: String,
+    pub track_title: Option<String>,
+    pub metadata: CampaignMetadata,
+    pub dj_count: usize,
+    pub last_checked: Option<String>,
+    /// Why the last check was considered suspect; empty when the page parsed normally
+    pub scrape_issues: Vec<String>,
+}
+
+/// Health check endpoint
+async fn health_check() -> &'static str {
+    "OK"
+}
+
+/// Get current campaigns being monitored
+async fn get_campaigns(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let stats = state.campaign_stats.read().await;
+    Json(serde_json::json!({
+        "status": "active",
+        "campaigns": stats.clone(),
+        "total_campaigns": stats.len(),
+        "check_interval_minutes": state.config.check_interval_minutes,
+    }))
+}
+
+/// Look up a monitored campaign by name for HTTP handlers
+fn find_campaign<'a>(
+    state: &'a AppState,
+    name: &str,
+) -> Result<&'a Campaign, (StatusCode, String)> {
+    state
+        .config
+        .campaigns
+        .iter()
+        .find(|c| c.name == name)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Unknown campaign: {}", name)))
+}
+
+/// Get the event log of a campaign, plus every support ever observed where the backend
+/// keeps first/last seen timestamps
+async fn get_campaign_history(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let campaign = find_campaign(&state, &name)?;
+
+    let events = state
+        .store
+        .events(campaign)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let supports = state.store.history(campaign).await.ok();
+
+    Ok(Json(serde_json::json!({
+        "campaign": campaign.name,
+        "track_title": campaign.metadata.track_title(),
+        "events": events,
+        "supports": supports,
+    })))
+}
+
+/// List the retained snapshots of a campaign
+async fn get_campaign_snapshots(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let campaign = find_campaign(&state, &name)?;
+
+    let snapshots = state
+        .store
+        .snapshots(campaign)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "campaign": campaign.name,
+        "snapshots": snapshots,
+    })))
+}
+
+/// Get the DJ list as it was in one snapshot
+async fn get_campaign_snapshot(
+    State(state): State<AppState>,
+    Path((name, id)): Path<(String, String)>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let campaign = find_campaign(&state, &name)?;
+
+    let djs = state
+        .store
+        .load_snapshot(campaign, &id)
+        .await
+        .map_err(|e| (StatusCode::NOT_FOUND, e.to_string()))?;
+
+    Ok(Json(serde_json::json!({
+        "campaign": campaign.name,
+        "snapshot": id,
+        "dj_count": djs.len(),
+        "djs": djs,
+    })))
+}
+
+/// Update campaign statistics
+pub async fn update_campaign_stats(
+    state: &AppState,
+    campaign: &Campaign,
+    dj_count: usize,
+    issues: &[HealthIssue],
+) -> Result<()> {
+    let mut stats = state.campaign_stats.write().await;
+    let scrape_issues = issues.iter().map(|i| i.to_string()).collect();
+
+    // Find existing stat or create new one
+    if let Some(stat) = stats.iter_mut().find(|s| s.name == campaign.name) {
+        stat.track_title = campaign.metadata.track_title();
+        stat.metadata = campaign.metadata.clone();
+        stat.dj_count = dj_count;
+        stat.last_checked = Some(chrono::Utc::now().to_rfc3339());
+        stat.scrape_issues = scrape_issues;
+    } else {
+        stats.push(CampaignStats {
+            name: campaign.name.clone(),
+            url: campaign.url.clone(),
+            track_title: campaign.metadata.track_title(),
+            metadata: campaign.metadata.clone(),
+            dj_count,
+            last_checked: Some(chrono::Utc::now().to_rfc3339()),
+            scrape_issues,
+        });
+    }
+
+    Ok(())
+}
+
+/// An outbox entry as listed by the HTTP endpoint, without its recipients or errors
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboxStatus {
+    pub campaign: String,
+    pub channel: String,
+    pub events: usize,
+    pub attempts: u32,
+    pub dead: bool,
+}
+
+/// How many alerts are waiting to be delivered and how many were given up on
+async fn get_outbox(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let entries: Vec<OutboxStatus> = state
+        .store
+        .load_outbox()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .into_iter()
+        .map(|entry| OutboxStatus {
+            campaign: entry.campaign,
+            channel: entry.channel,
+            events: entry.events.len(),
+            attempts: entry.attempts,
+            dead: entry.dead,
+        })
+        .collect();
+    let dead = entries.iter().filter(|entry| entry.dead).count();
+
+    Ok(Json(serde_json::json!({
+        "pending": entries.len() - dead,
+        "dead": dead,
+        "entries": entries,
+    })))
+}
+
+/// Start HTTP server
+pub async fn start_http_server(state: AppState, port: u16) {
+    let app = Router::new()
+        .route("/health", get(health_check))
+        .route("/campaigns", get(get_campaigns))
+        .route("/campaigns/:name/history", get(get_campaign_history))
+        .route("/campaigns/:name/snapshots", get(get_campaign_snapshots))
+        .route("/campaigns/:name/snapshots/:id", get(get_campaign_snapshot))
+        .route("/outbox", get(get_outbox))
+        .layer(CorsLayer::permissive())
+        .with_state(state);
+
+    let addr = format!("0.0.0.0:{}", port);
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .expect("Failed to bind HTTP server");
+
+    info!(address = %addr, "HTTP server listening");
+    info!("Health endpoint: http://{}/health", addr);
+    info!("Campaigns endpoint: http://{}/campaigns", addr);
+    info!("History endpoint: http://{}/campaigns/<name>/history", addr);
+    info!(
+        "Snapshots endpoint: http://{}/campaigns/<name>/snapshots",
+        addr
+    );
+    info!("Outbox endpoint: http://{}/outbox", addr);
+
+    axum::serve(listener, app)
+        .await
+        .expect("HTTP server failed");
+}
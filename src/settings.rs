@@ -0,0 +1,174 @@
+use anyhow::{Context, Result};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::quiet::QuietHours;
+use crate::rules::AlertRules;
+
+/// Per-campaign overrides of the global configuration
+///
+/// Loaded from the JSON file named by `CAMPAIGN_SETTINGS_FILE`, keyed by campaign name:
+///
+/// ```json
+/// { "pmqtne": { "slack_webhook_url": "https://hooks.slack.com/services/..." } }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CampaignSettings {
+    /// Email addresses receiving this campaign's alerts, besides `RECIPIENT_EMAIL`
+    pub recipients: Vec<Recipient>,
+    /// Channels this campaign's alerts are sent to (`email`, `mailgun`, `smtp`, `slack`,
+    /// `discord`, `telegram`, `webhook`); every enabled channel when unset
+    pub channels: Option<Vec<String>>,
+    /// Slack incoming webhook posting to this campaign's channel
+    pub slack_webhook_url: Option<String>,
+    /// Discord webhook posting to this campaign's channel
+    pub discord_webhook_url: Option<String>,
+    /// Telegram chats (user, group or channel IDs) receiving this campaign's alerts
+    pub telegram_chat_ids: Vec<Recipient>,
+    /// URLs receiving this campaign's signed JSON events
+    pub webhook_urls: Vec<String>,
+    /// Alert on every change even when digests are enabled, leaving the campaign out of
+    /// the digest
+    pub immediate_alerts: bool,
+    /// Rules applied on top of the global alert rules
+    pub alert_rules: Option<AlertRules>,
+    /// Replaces the global quiet hours for this campaign's alerts (`off` to disable them)
+    pub quiet_hours: Option<QuietHours>,
+    /// Timezone of this campaign's recipients, for its quiet hours
+    pub timezone: Option<Tz>,
+}
+
+impl CampaignSettings {
+    /// The listed email recipient or Telegram chat with the given address or ID
+    pub fn recipient(&self, to: &str) -> Option<&Recipient> {
+        self.recipients
+            .iter()
+            .chain(&self.telegram_chat_ids)
+            .find(|r| r.to.eq_ignore_ascii_case(to))
+    }
+
+    /// The quiet hours and timezone that apply to one destination of this campaign's
+    /// alerts: the recipient's own, then the campaign's, then the given global ones
+    pub fn quiet_hours_for(
+        &self,
+        to: Option<&str>,
+        quiet_hours: Option<QuietHours>,
+        timezone: Tz,
+    ) -> (Option<QuietHours>, Tz) {
+        let recipient = to.and_then(|to| self.recipient(to));
+        (
+            recipient
+                .and_then(|r| r.quiet_hours)
+                .or(self.quiet_hours)
+                .or(quiet_hours),
+            recipient
+                .and_then(|r| r.timezone)
+                .or(self.timezone)
+                .unwrap_or(timezone),
+        )
+    }
+}
+
+/// An email address or Telegram chat, optionally with quiet hours of its own
+///
+/// Written as a plain string, or as an object:
+///
+/// ```json
+/// { "to": "label@example.jp", "quiet_hours": "23:00-07:00", "timezone": "Asia/Tokyo" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "RecipientEntry")]
+pub struct Recipient {
+    pub to: String,
+    /// Replaces the campaign's quiet hours for this recipient (`off` to disable them)
+    pub quiet_hours: Option<QuietHours>,
+    /// Timezone of this recipient, for their quiet hours
+    pub timezone: Option<Tz>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RecipientEntry {
+    To(String),
+    Detailed {
+        to: String,
+        #[serde(default)]
+        quiet_hours: Option<QuietHours>,
+        #[serde(default)]
+        timezone: Option<Tz>,
+    },
+}
+
+impl From<RecipientEntry> for Recipient {
+    fn from(entry: RecipientEntry) -> Self {
+        match entry {
+            RecipientEntry::To(to) => Recipient {
+                to,
+                quiet_hours: None,
+                timezone: None,
+            },
+            RecipientEntry::Detailed {
+                to,
+                quiet_hours,
+                timezone,
+            } => Recipient {
+                to,
+                quiet_hours,
+                timezone,
+            },
+        }
+    }
+}
+
+/// Load the settings of every campaign listed in the file
+pub fn load_campaign_settings(path: &Path) -> Result<HashMap<String, CampaignSettings>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read campaign settings: {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse campaign settings: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quiet_hours_per_recipient() {
+        let settings: CampaignSettings = serde_json::from_str(
+            r#"{
+                "recipients": [
+                    "label@example.com",
+                    { "to": "artist@example.jp", "quiet_hours": "23:00-07:00", "timezone": "Asia/Tokyo" }
+                ],
+                "telegram_chat_ids": [{ "to": "-1001234567890", "quiet_hours": "off" }],
+                "quiet_hours": "22:00-08:00"
+            }"#,
+        )
+        .unwrap();
+        let berlin: Tz = "Europe/Berlin".parse().unwrap();
+        let global = Some("21:00-09:00".parse().unwrap());
+        let quiet = |to| settings.quiet_hours_for(to, global, berlin);
+
+        assert_eq!(
+            quiet(Some("label@example.com")),
+            (settings.quiet_hours, berlin)
+        );
+        assert_eq!(
+            quiet(Some("Artist@Example.jp")),
+            (Some("23:00-07:00".parse().unwrap()), Tz::Asia__Tokyo)
+        );
+        assert_eq!(
+            quiet(Some("-1001234567890")),
+            (Some("off".parse().unwrap()), berlin)
+        );
+        // Slack and Discord deliver to the whole channel
+        assert_eq!(quiet(None), (settings.quiet_hours, berlin));
+
+        let unknown = serde_json::from_str::<CampaignSettings>(r#"{ "recipients": [{}] }"#);
+        assert!(unknown.is_err());
+    }
+}
@@ -0,0 +1,60 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use azure_storage_blobs::prelude::*;
+use futures::StreamExt;
+
+use super::versioned::BlobBackend;
+
+/// Objects stored as blobs in an Azure Blob Storage container
+pub struct AzureBlobs {
+    pub(super) container_client: ContainerClient,
+}
+
+#[async_trait]
+impl BlobBackend for AzureBlobs {
+    async fn get(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        match self.container_client.blob_client(name).get_content().await {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.as_http_error().is_some_and(|h| h.status() as u16 == 404) => {
+                // Blob doesn't exist yet (first run)
+                Ok(None)
+            }
+            Err(e) => Err(e).with_context(|| format!("Failed to download blob {}", name)),
+        }
+    }
+
+    async fn put(&self, name: &str, content: Vec<u8>) -> Result<()> {
+        self.container_client
+            .blob_client(name)
+            .put_block_blob(content)
+            .content_type("application/json")
+            .await
+            .with_context(|| format!("Failed to upload blob {}", name))?;
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        let mut pages = self
+            .container_client
+            .list_blobs()
+            .prefix(prefix.to_string())
+            .into_stream();
+
+        while let Some(page) = pages.next().await {
+            let page = page.context("Failed to list blobs")?;
+            names.extend(page.blobs.blobs().map(|blob| blob.name.clone()));
+        }
+
+        Ok(names)
+    }
+
+    async fn delete(&self, name: &str) -> Result<()> {
+        self.container_client
+            .blob_client(name)
+            .delete()
+            .await
+            .with_context(|| format!("Failed to delete blob {}", name))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+use super::versioned::BlobBackend;
+
+/// Objects stored as files in a local directory; `/` in names maps to subdirectories
+pub struct LocalDir {
+    pub(super) dir: PathBuf,
+}
+
+#[async_trait]
+impl BlobBackend for LocalDir {
+    async fn get(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.dir.join(name);
+
+        match tokio::fs::read(&path).await {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                // File doesn't exist yet (first run)
+                Ok(None)
+            }
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    async fn put(&self, name: &str, content: Vec<u8>) -> Result<()> {
+        let path = self.dir.join(name);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+
+        // Write to a temporary file first so a crash never leaves a truncated file behind
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, content)
+            .await
+            .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+        tokio::fs::rename(&tmp_path, &path)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        // Only prefixes ending in a directory separator are needed
+        let dir = self.dir.join(prefix);
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to list {}", dir.display()));
+            }
+        };
+
+        let mut names = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            if let Some(file_name) = entry.file_name().to_str()
+                && !file_name.ends_with(".tmp")
+            {
+                names.push(format!("{}{}", prefix, file_name));
+            }
+        }
+
+        Ok(names)
+    }
+
+    async fn delete(&self, name: &str) -> Result<()> {
+        let path = self.dir.join(name);
+        tokio::fs::remove_file(&path)
+            .await
+            .with_context(|| format!("Failed to delete {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let local = LocalDir {
+            dir: dir.path().to_path_buf(),
+        };
+
+        assert_eq!(local.get("dj_list_c1.json").await.unwrap(), None);
+        assert!(
+            local
+                .list("dj_list_c1/snapshots/")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        local
+            .put("dj_list_c1/snapshots/a.json", b"first".to_vec())
+            .await
+            .unwrap();
+        local
+            .put("dj_list_c1/snapshots/a.json", b"second".to_vec())
+            .await
+            .unwrap();
+        local
+            .put("dj_list_c1/snapshots/b.json", b"other".to_vec())
+            .await
+            .unwrap();
+        // Leftovers of an interrupted write are not listed
+        std::fs::write(
+            dir.path().join("dj_list_c1/snapshots/c.json.tmp"),
+            "partial",
+        )
+        .unwrap();
+
+        assert_eq!(
+            local.get("dj_list_c1/snapshots/a.json").await.unwrap(),
+            Some(b"second".to_vec())
+        );
+        let mut names = local.list("dj_list_c1/snapshots/").await.unwrap();
+        names.sort();
+        assert_eq!(
+            names,
+            ["dj_list_c1/snapshots/a.json", "dj_list_c1/snapshots/b.json"]
+        );
+
+        local.delete("dj_list_c1/snapshots/a.json").await.unwrap();
+        assert_eq!(
+            local.get("dj_list_c1/snapshots/a.json").await.unwrap(),
+            None
+        );
+        assert!(local.delete("dj_list_c1/snapshots/a.json").await.is_err());
+    }
+}
@@ -0,0 +1,328 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use azure_storage::StorageCredentials;
+use azure_storage_blobs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::diff::SupportEvent;
+use crate::metadata::CampaignMetadata;
+use crate::monitor::CampaignSummary;
+use crate::names::NameNormalizer;
+use crate::outbox::OutboxEntry;
+use crate::{Campaign, DjStorage, DjSupport};
+
+mod azure;
+mod local;
+mod sqlite;
+mod versioned;
+
+use azure::AzureBlobs;
+use local::LocalDir;
+pub use sqlite::SqliteStore;
+pub use versioned::{BlobBackend, VersionedStore};
+
+/// Storage backend selected via the `STORAGE_BACKEND` environment variable
+#[derive(Debug, Clone)]
+pub enum StorageConfig {
+    Azure {
+        account: String,
+        container: String,
+        credentials: StorageCredentials,
+    },
+    Local {
+        dir: PathBuf,
+    },
+    Sqlite {
+        path: PathBuf,
+    },
+}
+
+impl StorageConfig {
+    pub fn from_env() -> Result<Self> {
+        let backend = env::var("STORAGE_BACKEND").unwrap_or_else(|_| "azure".to_string());
+
+        match backend.to_lowercase().as_str() {
+            "azure" => {
+                let account = env::var("AZURE_STORAGE_ACCOUNT")
+                    .context("AZURE_STORAGE_ACCOUNT environment variable not set")?;
+
+                let credentials = if let Ok(access_key) = env::var("AZURE_STORAGE_ACCESS_KEY") {
+                    StorageCredentials::access_key(account.clone(), access_key)
+                } else if let Ok(sas_token) = env::var("AZURE_STORAGE_SAS_TOKEN") {
+                    StorageCredentials::sas_token(sas_token)?
+                } else {
+                    anyhow::bail!(
+                        "Either AZURE_STORAGE_ACCESS_KEY or AZURE_STORAGE_SAS_TOKEN must be set"
+                    )
+                };
+
+                Ok(StorageConfig::Azure {
+                    account,
+                    container: env::var("AZURE_STORAGE_CONTAINER")
+                        .unwrap_or_else(|_| "inflyte-dj-monitor".to_string()),
+                    credentials,
+                })
+            }
+            "local" => Ok(StorageConfig::Local {
+                dir: env::var("LOCAL_STORAGE_DIR")
+                    .unwrap_or_else(|_| "data".to_string())
+                    .into(),
+            }),
+            "sqlite" => Ok(StorageConfig::Sqlite {
+                path: env::var("SQLITE_PATH")
+                    .unwrap_or_else(|_| "data/inflyte.db".to_string())
+                    .into(),
+            }),
+            other => anyhow::bail!(
+                "Unknown STORAGE_BACKEND '{}' (expected 'azure', 'local' or 'sqlite')",
+                other
+            ),
+        }
+    }
+
+    /// Log the storage configuration at startup
+    pub fn log_summary(&self) {
+        match self {
+            StorageConfig::Azure {
+                account, container, ..
+            } => {
+                info!("  Storage Backend: Azure Blob Storage");
+                info!("  Azure Storage Account: {}", account);
+                info!("  Azure Container: {}", container);
+            }
+            StorageConfig::Local { dir } => {
+                info!("  Storage Backend: Local filesystem");
+                info!("  Storage Directory: {}", dir.display());
+            }
+            StorageConfig::Sqlite { path } => {
+                info!("  Storage Backend: SQLite");
+                info!("  Database: {}", path.display());
+            }
+        }
+    }
+}
+
+/// How many snapshots to keep per campaign (both limits apply when set)
+#[derive(Debug, Clone, Default)]
+pub struct RetentionPolicy {
+    /// Keep at most this many snapshots
+    pub max_count: Option<usize>,
+    /// Delete snapshots older than this many days
+    pub max_age_days: Option<u64>,
+}
+
+impl RetentionPolicy {
+    pub fn from_env() -> Result<Self> {
+        Ok(RetentionPolicy {
+            max_count: env::var("SNAPSHOT_RETENTION_COUNT")
+                .ok()
+                .map(|v| v.parse())
+                .transpose()
+                .context("SNAPSHOT_RETENTION_COUNT must be a valid number")?,
+            max_age_days: env::var("SNAPSHOT_RETENTION_DAYS")
+                .ok()
+                .map(|v| v.parse())
+                .transpose()
+                .context("SNAPSHOT_RETENTION_DAYS must be a valid number")?,
+        })
+    }
+
+    pub fn is_limited(&self) -> bool {
+        self.max_count.is_some() || self.max_age_days.is_some()
+    }
+
+    /// Log the retention configuration at startup
+    pub fn log_summary(&self) {
+        match (self.max_count, self.max_age_days) {
+            (None, None) => info!("  Snapshot Retention: keep all"),
+            (count, days) => info!(
+                "  Snapshot Retention: {} snapshots, {} days",
+                count.map_or("unlimited".to_string(), |c| c.to_string()),
+                days.map_or("unlimited".to_string(), |d| d.to_string())
+            ),
+        }
+    }
+}
+
+/// A stored snapshot of a campaign's DJ list
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub taken_at: String,
+}
+
+/// A DJ support together with when it was first and last observed
+#[derive(Debug, Clone, Serialize)]
+pub struct SupportRecord {
+    #[serde(flatten)]
+    pub dj: DjSupport,
+    pub first_seen: String,
+    pub last_seen: String,
+    /// Whether the support was present in the most recent check
+    pub current: bool,
+}
+
+/// Persistence for the DJ list of each campaign
+#[async_trait]
+pub trait DjStore: Send + Sync {
+    /// Load the previously saved DJ list (empty if nothing has been saved yet)
+    async fn load(&self, campaign: &Campaign) -> Result<HashSet<DjSupport>>;
+
+    /// Save the current DJ list as a new snapshot and make it the current one
+    async fn save(&self, campaign: &Campaign, djs: &HashSet<DjSupport>) -> Result<()>;
+
+    /// All retained snapshots of a campaign, oldest first
+    async fn snapshots(&self, campaign: &Campaign) -> Result<Vec<SnapshotInfo>>;
+
+    /// The DJ list as it was in a given snapshot
+    async fn load_snapshot(&self, campaign: &Campaign, id: &str) -> Result<HashSet<DjSupport>>;
+
+    /// Append detected changes to the campaign's event log
+    async fn record_events(&self, campaign: &Campaign, events: &[SupportEvent]) -> Result<()>;
+
+    /// The campaign's event log, oldest first
+    async fn events(&self, campaign: &Campaign) -> Result<Vec<SupportEvent>>;
+
+    /// The campaign metadata saved by the last check that found any
+    async fn load_metadata(&self, campaign: &Campaign) -> Result<Option<CampaignMetadata>>;
+
+    /// Replace the campaign's saved metadata
+    async fn save_metadata(&self, campaign: &Campaign, metadata: &CampaignMetadata) -> Result<()>;
+
+    /// Why the campaign's last check was suspect; empty when its page parsed normally
+    async fn load_scrape_issues(&self, campaign: &Campaign) -> Result<Vec<String>>;
+
+    /// Replace the campaign's scrape issues, clearing them with an empty list
+    async fn save_scrape_issues(&self, campaign: &Campaign, issues: &[String]) -> Result<()>;
+
+    /// Alerts waiting to be delivered, plus dead letters, across all campaigns
+    async fn load_outbox(&self) -> Result<Vec<OutboxEntry>>;
+
+    /// Replace the stored outbox
+    async fn save_outbox(&self, entries: &[OutboxEntry]) -> Result<()>;
+
+    /// Record how a check of the campaign went, including suspect and failed checks that
+    /// left the DJ list alone; backends without a check log ignore it
+    async fn record_check(&self, _summary: &CampaignSummary) -> Result<()> {
+        Ok(())
+    }
+
+    /// Every support ever observed for a campaign, oldest first
+    async fn history(&self, _campaign: &Campaign) -> Result<Vec<SupportRecord>> {
+        anyhow::bail!("Support history is not available for this storage backend")
+    }
+}
+
+/// Create the store for the configured backend
+pub fn build_store(
+    config: &StorageConfig,
+    blob_name_prefix: &str,
+    retention: &RetentionPolicy,
+    names: &NameNormalizer,
+) -> Result<Arc<dyn DjStore>> {
+    Ok(match config {
+        StorageConfig::Azure {
+            account,
+            container,
+            credentials,
+        } => Arc::new(VersionedStore::new(
+            AzureBlobs {
+                container_client: BlobServiceClient::new(account.clone(), credentials.clone())
+                    .container_client(container),
+            },
+            blob_name_prefix,
+            retention.clone(),
+        )),
+        StorageConfig::Local { dir } => Arc::new(VersionedStore::new(
+            LocalDir { dir: dir.clone() },
+            blob_name_prefix,
+            retention.clone(),
+        )),
+        StorageConfig::Sqlite { path } => {
+            Arc::new(SqliteStore::open(path, retention.clone(), names.clone())?)
+        }
+    })
+}
+
+/// Fail for campaign names that can't be used in blob and file names
+///
+/// Names with path separators could reach into another campaign's blobs or the shared
+/// outbox, which live under the bare prefix.
+pub fn check_campaign_name(name: &str) -> Result<()> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        anyhow::bail!("Invalid campaign name '{}'", name);
+    }
+    Ok(())
+}
+
+/// Get blob name for a campaign
+fn get_blob_name(prefix: &str, campaign: &Campaign) -> String {
+    format!("{}_{}.json", prefix, campaign.name)
+}
+
+/// Parse a stored DJ list, migrating the old name-only format if necessary
+fn decode_dj_list(content: &str) -> Result<HashSet<DjSupport>> {
+    // Try to parse as new format first
+    if let Ok(storage) = serde_json::from_str::<DjStorage>(content) {
+        return Ok(storage.djs);
+    }
+
+    // Try to migrate from old format (HashSet<String>)
+    #[derive(Deserialize)]
+    struct OldDjStorage {
+        djs: HashSet<String>,
+    }
+
+    if let Ok(old_storage) = serde_json::from_str::<OldDjStorage>(content) {
+        info!("Migrating old DJ storage format to new format with comment/rating support");
+        Ok(old_storage
+            .djs
+            .into_iter()
+            .map(|name| DjSupport {
+                name,
+                comment: None,
+                stars: None,
+            })
+            .collect())
+    } else {
+        anyhow::bail!("Failed to parse DJ storage JSON in either old or new format")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::dj;
+
+    #[test]
+    fn decodes_current_and_old_dj_lists() {
+        let current =
+            decode_dj_list(r#"{"djs": [{"name": "Dixon", "comment": "Nice", "stars": 4}]}"#)
+                .unwrap();
+        assert_eq!(current, HashSet::from([dj("Dixon", Some("Nice"), Some(4))]));
+
+        // Before comments and ratings were tracked, only names were stored
+        let old = decode_dj_list(r#"{"djs": ["Dixon", "Kölsch"]}"#).unwrap();
+        assert_eq!(
+            old,
+            HashSet::from([dj("Dixon", None, None), dj("Kölsch", None, None)])
+        );
+
+        assert!(decode_dj_list(r#"{"names": []}"#).is_err());
+        assert!(decode_dj_list("not json").is_err());
+    }
+
+    #[test]
+    fn campaign_names_stay_inside_their_blobs() {
+        assert!(check_campaign_name("pmqtne").is_ok());
+        assert!(check_campaign_name("outbox").is_ok());
+        for name in ["", ".", "..", "a/b", "..\\outbox"] {
+            assert!(check_campaign_name(name).is_err(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,794 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use super::{DjStore, RetentionPolicy, SnapshotInfo, SupportRecord};
+use crate::diff::{EventKind, SupportEvent};
+use crate::metadata::CampaignMetadata;
+use crate::monitor::CampaignSummary;
+use crate::names::NameNormalizer;
+use crate::outbox::OutboxEntry;
+use crate::{Campaign, DjSupport};
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS checks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    campaign TEXT NOT NULL,
+    checked_at TEXT NOT NULL,
+    dj_count INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_checks_campaign ON checks (campaign, id);
+
+CREATE TABLE IF NOT EXISTS check_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    campaign TEXT NOT NULL,
+    checked_at TEXT NOT NULL,
+    status TEXT NOT NULL,
+    dj_count INTEGER,
+    new INTEGER NOT NULL,
+    updated INTEGER NOT NULL,
+    removed INTEGER NOT NULL,
+    error TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_check_runs_campaign ON check_runs (campaign, id);
+
+CREATE TABLE IF NOT EXISTS supports (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    campaign TEXT NOT NULL,
+    identity TEXT NOT NULL,
+    name TEXT NOT NULL,
+    comment TEXT,
+    stars INTEGER,
+    first_seen TEXT NOT NULL,
+    last_seen TEXT NOT NULL
+);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_supports_identity ON supports (campaign, identity);
+
+CREATE TABLE IF NOT EXISTS observations (
+    check_id INTEGER NOT NULL REFERENCES checks (id),
+    support_id INTEGER NOT NULL REFERENCES supports (id),
+    name TEXT NOT NULL,
+    comment TEXT,
+    stars INTEGER,
+    PRIMARY KEY (check_id, support_id)
+);
+
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    campaign TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    name TEXT NOT NULL,
+    comment TEXT,
+    stars INTEGER,
+    previous TEXT,
+    detected_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_events_campaign ON events (campaign, id);
+
+CREATE TABLE IF NOT EXISTS campaign_metadata (
+    campaign TEXT PRIMARY KEY,
+    metadata TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS campaign_health (
+    campaign TEXT PRIMARY KEY,
+    issues TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS outbox (
+    id TEXT PRIMARY KEY,
+    entry TEXT NOT NULL
+);
+"#;
+
+/// Stores every check run and every observed DJ support in an embedded SQLite database
+///
+/// A support is one DJ per campaign, keyed by DJ identity (see [`NameNormalizer`]) and
+/// holding the DJ's latest name, comment and stars. Each check records every DJ as listed
+/// at the time, so the DJ list at any past check can be rebuilt and earlier comments are
+/// never lost. Each check doubles as a snapshot; the retention policy prunes old checks
+/// while the first/last seen timestamps of supports are kept forever.
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+    retention: RetentionPolicy,
+    names: NameNormalizer,
+}
+
+impl SqliteStore {
+    pub fn open(path: &Path, retention: RetentionPolicy, names: NameNormalizer) -> Result<Self> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open SQLite database {}", path.display()))?;
+        conn.execute_batch(SCHEMA)
+            .context("Failed to initialize SQLite schema")?;
+
+        Ok(SqliteStore {
+            conn: Arc::new(Mutex::new(conn)),
+            retention,
+            names,
+        })
+    }
+
+    /// Run a blocking database operation off the async runtime
+    async fn with_conn<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn
+                .lock()
+                .map_err(|_| anyhow::anyhow!("SQLite connection lock poisoned"))?;
+            f(&mut conn)
+        })
+        .await
+        .context("SQLite task panicked")?
+    }
+}
+
+#[async_trait]
+impl DjStore for SqliteStore {
+    async fn load(&self, campaign: &Campaign) -> Result<HashSet<DjSupport>> {
+        let campaign_name = campaign.name.clone();
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT name, comment, stars FROM observations
+                 WHERE check_id = (SELECT MAX(id) FROM checks WHERE campaign = ?1)",
+            )?;
+            let djs = stmt
+                .query_map(params![campaign_name], |row| {
+                    Ok(DjSupport {
+                        name: row.get(0)?,
+                        comment: row.get(1)?,
+                        stars: row.get(2)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<HashSet<_>>>()
+                .context("Failed to load DJ list from SQLite")?;
+            Ok(djs)
+        })
+        .await
+    }
+
+    async fn save(&self, campaign: &Campaign, djs: &HashSet<DjSupport>) -> Result<()> {
+        let campaign_name = campaign.name.clone();
+        let djs: Vec<(String, DjSupport)> = djs
+            .iter()
+            .map(|dj| (self.names.identity(&dj.name), dj.clone()))
+            .collect();
+        let retention = self.retention.clone();
+        self.with_conn(move |conn| {
+            let now = chrono::Utc::now().to_rfc3339();
+            let tx = conn.transaction()?;
+
+            tx.execute(
+                "INSERT INTO checks (campaign, checked_at, dj_count) VALUES (?1, ?2, ?3)",
+                params![campaign_name, now, djs.len()],
+            )?;
+            let check_id = tx.last_insert_rowid();
+
+            for (identity, dj) in &djs {
+                // The support follows the DJ's latest listing
+                let support_id: i64 = tx.query_row(
+                    "INSERT INTO supports
+                         (campaign, identity, name, comment, stars, first_seen, last_seen)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+                     ON CONFLICT (campaign, identity) DO UPDATE
+                     SET name = excluded.name, comment = excluded.comment,
+                         stars = excluded.stars, last_seen = excluded.last_seen
+                     RETURNING id",
+                    params![campaign_name, identity, dj.name, dj.comment, dj.stars, now],
+                    |row| row.get(0),
+                )?;
+
+                tx.execute(
+                    "INSERT OR IGNORE INTO observations (check_id, support_id, name, comment, stars)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![check_id, support_id, dj.name, dj.comment, dj.stars],
+                )?;
+            }
+
+            prune_checks(&tx, &campaign_name, check_id, &retention)?;
+
+            tx.commit().context("Failed to save DJ list to SQLite")?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn snapshots(&self, campaign: &Campaign) -> Result<Vec<SnapshotInfo>> {
+        let campaign_name = campaign.name.clone();
+        self.with_conn(move |conn| {
+            let mut stmt =
+                conn.prepare("SELECT id, checked_at FROM checks WHERE campaign = ?1 ORDER BY id")?;
+            let snapshots = stmt
+                .query_map(params![campaign_name], |row| {
+                    Ok(SnapshotInfo {
+                        id: row.get::<_, i64>(0)?.to_string(),
+                        taken_at: row.get(1)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .context("Failed to list checks from SQLite")?;
+            Ok(snapshots)
+        })
+        .await
+    }
+
+    async fn load_snapshot(&self, campaign: &Campaign, id: &str) -> Result<HashSet<DjSupport>> {
+        let campaign_name = campaign.name.clone();
+        let check_id: i64 = id
+            .parse()
+            .with_context(|| format!("Invalid snapshot ID: {}", id))?;
+        self.with_conn(move |conn| {
+            let exists: bool = conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM checks WHERE id = ?1 AND campaign = ?2)",
+                params![check_id, campaign_name],
+                |row| row.get(0),
+            )?;
+            if !exists {
+                anyhow::bail!("Snapshot {} does not exist", check_id);
+            }
+
+            let mut stmt =
+                conn.prepare("SELECT name, comment, stars FROM observations WHERE check_id = ?1")?;
+            let djs = stmt
+                .query_map(params![check_id], |row| {
+                    Ok(DjSupport {
+                        name: row.get(0)?,
+                        comment: row.get(1)?,
+                        stars: row.get(2)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<HashSet<_>>>()
+                .context("Failed to load snapshot from SQLite")?;
+            Ok(djs)
+        })
+        .await
+    }
+
+    async fn record_events(&self, campaign: &Campaign, events: &[SupportEvent]) -> Result<()> {
+        let campaign_name = campaign.name.clone();
+        let events = events.to_vec();
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            for event in &events {
+                tx.execute(
+                    "INSERT INTO events (campaign, kind, name, comment, stars, previous, detected_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        campaign_name,
+                        serde_json::to_value(event.kind)?.as_str(),
+                        event.dj.name,
+                        event.dj.comment,
+                        event.dj.stars,
+                        event
+                            .previous
+                            .as_ref()
+                            .map(serde_json::to_string)
+                            .transpose()?,
+                        event.detected_at,
+                    ],
+                )?;
+            }
+            tx.commit().context("Failed to save events to SQLite")?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn events(&self, campaign: &Campaign) -> Result<Vec<SupportEvent>> {
+        let campaign_name = campaign.name.clone();
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT kind, name, comment, stars, previous, detected_at
+                 FROM events WHERE campaign = ?1 ORDER BY id",
+            )?;
+            let rows = stmt
+                .query_map(params![campaign_name], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        DjSupport {
+                            name: row.get(1)?,
+                            comment: row.get(2)?,
+                            stars: row.get(3)?,
+                        },
+                        row.get::<_, Option<String>>(4)?,
+                        row.get::<_, String>(5)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .context("Failed to load events from SQLite")?;
+
+            rows.into_iter()
+                .map(|(kind, dj, previous, detected_at)| {
+                    let kind: EventKind = serde_json::from_value(kind.into())
+                        .context("Unknown event kind in SQLite")?;
+                    let previous = previous
+                        .map(|json| serde_json::from_str(&json))
+                        .transpose()
+                        .context("Failed to parse previous DJ support in SQLite")?;
+                    Ok(SupportEvent {
+                        kind,
+                        dj,
+                        previous,
+                        detected_at,
+                    })
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn history(&self, campaign: &Campaign) -> Result<Vec<SupportRecord>> {
+        let campaign_name = campaign.name.clone();
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT s.name, s.comment, s.stars, s.first_seen, s.last_seen,
+                        EXISTS (
+                            SELECT 1 FROM observations o
+                            WHERE o.support_id = s.id
+                              AND o.check_id = (SELECT MAX(id) FROM checks WHERE campaign = ?1)
+                        )
+                 FROM supports s
+                 WHERE s.campaign = ?1
+                 ORDER BY s.first_seen, s.name",
+            )?;
+            let records = stmt
+                .query_map(params![campaign_name], |row| {
+                    Ok(SupportRecord {
+                        dj: DjSupport {
+                            name: row.get(0)?,
+                            comment: row.get(1)?,
+                            stars: row.get(2)?,
+                        },
+                        first_seen: row.get(3)?,
+                        last_seen: row.get(4)?,
+                        current: row.get(5)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .context("Failed to load support history from SQLite")?;
+            Ok(records)
+        })
+        .await
+    }
+
+    async fn load_metadata(&self, campaign: &Campaign) -> Result<Option<CampaignMetadata>> {
+        let campaign_name = campaign.name.clone();
+        self.with_conn(move |conn| {
+            let json: Option<String> = conn
+                .query_row(
+                    "SELECT metadata FROM campaign_metadata WHERE campaign = ?1",
+                    params![campaign_name],
+                    |row| row.get(0),
+                )
+                .optional()
+                .context("Failed to load campaign metadata from SQLite")?;
+            json.map(|json| serde_json::from_str(&json))
+                .transpose()
+                .context("Failed to parse campaign metadata in SQLite")
+        })
+        .await
+    }
+
+    async fn save_metadata(&self, campaign: &Campaign, metadata: &CampaignMetadata) -> Result<()> {
+        let campaign_name = campaign.name.clone();
+        let json = serde_json::to_string(metadata)?;
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO campaign_metadata (campaign, metadata, updated_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (campaign) DO UPDATE
+                 SET metadata = excluded.metadata, updated_at = excluded.updated_at",
+                params![campaign_name, json, chrono::Utc::now().to_rfc3339()],
+            )
+            .context("Failed to save campaign metadata to SQLite")?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn load_scrape_issues(&self, campaign: &Campaign) -> Result<Vec<String>> {
+        let campaign_name = campaign.name.clone();
+        self.with_conn(move |conn| {
+            let json: Option<String> = conn
+                .query_row(
+                    "SELECT issues FROM campaign_health WHERE campaign = ?1",
+                    params![campaign_name],
+                    |row| row.get(0),
+                )
+                .optional()
+                .context("Failed to load scrape issues from SQLite")?;
+            json.map(|json| serde_json::from_str(&json))
+                .transpose()
+                .context("Failed to parse scrape issues in SQLite")
+                .map(Option::unwrap_or_default)
+        })
+        .await
+    }
+
+    async fn save_scrape_issues(&self, campaign: &Campaign, issues: &[String]) -> Result<()> {
+        let campaign_name = campaign.name.clone();
+        let json = serde_json::to_string(issues)?;
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO campaign_health (campaign, issues, updated_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (campaign) DO UPDATE
+                 SET issues = excluded.issues, updated_at = excluded.updated_at",
+                params![campaign_name, json, chrono::Utc::now().to_rfc3339()],
+            )
+            .context("Failed to save scrape issues to SQLite")?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn record_check(&self, summary: &CampaignSummary) -> Result<()> {
+        let outcome = summary.outcome.clone();
+        let status = serde_json::to_value(outcome.status)?
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let (campaign_name, error) = (summary.campaign.clone(), summary.error.clone());
+        self.with_conn(move |conn| {
+            conn.execute(
+                "INSERT INTO check_runs
+                     (campaign, checked_at, status, dj_count, new, updated, removed, error)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    campaign_name,
+                    chrono::Utc::now().to_rfc3339(),
+                    status,
+                    outcome.djs,
+                    outcome.new,
+                    outcome.updated,
+                    outcome.removed,
+                    error
+                ],
+            )
+            .context("Failed to record check in SQLite")?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn load_outbox(&self) -> Result<Vec<OutboxEntry>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare("SELECT entry FROM outbox ORDER BY rowid")?;
+            let rows = stmt
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .context("Failed to load outbox from SQLite")?;
+            rows.iter()
+                .map(|json| serde_json::from_str(json))
+                .collect::<serde_json::Result<Vec<_>>>()
+                .context("Failed to parse outbox entry in SQLite")
+        })
+        .await
+    }
+
+    async fn save_outbox(&self, entries: &[OutboxEntry]) -> Result<()> {
+        let rows = entries
+            .iter()
+            .map(|entry| Ok((entry.id.clone(), serde_json::to_string(entry)?)))
+            .collect::<Result<Vec<_>>>()?;
+        self.with_conn(move |conn| {
+            let tx = conn.transaction()?;
+            tx.execute("DELETE FROM outbox", [])?;
+            for (id, json) in &rows {
+                tx.execute(
+                    "INSERT INTO outbox (id, entry) VALUES (?1, ?2)",
+                    params![id, json],
+                )?;
+            }
+            tx.commit().context("Failed to save outbox to SQLite")?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// Delete checks (and their observations) that fall outside the retention policy
+fn prune_checks(
+    conn: &Connection,
+    campaign: &str,
+    keep: i64,
+    retention: &RetentionPolicy,
+) -> Result<()> {
+    let mut doomed: Vec<i64> = Vec::new();
+
+    if let Some(max_count) = retention.max_count {
+        let mut stmt = conn.prepare(
+            "SELECT id FROM checks WHERE campaign = ?1 ORDER BY id DESC LIMIT -1 OFFSET ?2",
+        )?;
+        doomed.extend(
+            stmt.query_map(params![campaign, max_count], |row| row.get::<_, i64>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?,
+        );
+    }
+
+    if let Some(days) = retention.max_age_days {
+        let cutoff = (chrono::Utc::now() - chrono::Duration::days(days as i64)).to_rfc3339();
+        let mut stmt =
+            conn.prepare("SELECT id FROM checks WHERE campaign = ?1 AND checked_at < ?2")?;
+        doomed.extend(
+            stmt.query_map(params![campaign, cutoff], |row| row.get::<_, i64>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?,
+        );
+    }
+
+    for id in doomed.into_iter().filter(|id| *id != keep) {
+        conn.execute("DELETE FROM observations WHERE check_id = ?1", params![id])?;
+        conn.execute("DELETE FROM checks WHERE id = ?1", params![id])?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{campaign, dj};
+
+    fn open_in_memory() -> SqliteStore {
+        open_with_retention(RetentionPolicy::default())
+    }
+
+    fn open_with_retention(retention: RetentionPolicy) -> SqliteStore {
+        SqliteStore::open(Path::new(":memory:"), retention, NameNormalizer::default()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn records_every_check_run() {
+        use crate::monitor::{CheckOutcome, CheckStatus};
+
+        let store = open_in_memory();
+        let c1 = campaign("c1");
+        store
+            .save(&c1, &HashSet::from([dj("Dixon", None, None)]))
+            .await
+            .unwrap();
+
+        let summary = |status, djs: Option<usize>, error: Option<&str>| CampaignSummary {
+            campaign: "c1".to_string(),
+            outcome: CheckOutcome {
+                status,
+                djs,
+                new: 0,
+                updated: 0,
+                removed: 0,
+                alerts: 0,
+            },
+            error: error.map(str::to_string),
+        };
+        store
+            .record_check(&summary(CheckStatus::Unchanged, Some(1), None))
+            .await
+            .unwrap();
+        store
+            .record_check(&summary(CheckStatus::Suspect, Some(1), None))
+            .await
+            .unwrap();
+        store
+            .record_check(&summary(
+                CheckStatus::Failed,
+                None,
+                Some("Failed to fetch webpage"),
+            ))
+            .await
+            .unwrap();
+
+        let runs: Vec<(String, Option<String>)> = {
+            let conn = store.conn.lock().unwrap();
+            let mut stmt = conn
+                .prepare("SELECT status, error FROM check_runs WHERE campaign = 'c1' ORDER BY id")
+                .unwrap();
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap()
+                .collect::<rusqlite::Result<_>>()
+                .unwrap()
+        };
+        assert_eq!(
+            runs,
+            [
+                ("unchanged".to_string(), None),
+                ("suspect".to_string(), None),
+                (
+                    "failed".to_string(),
+                    Some("Failed to fetch webpage".to_string())
+                ),
+            ]
+        );
+        // Recording a check leaves the DJ list and its snapshots alone
+        assert_eq!(store.snapshots(&c1).await.unwrap().len(), 1);
+        assert_eq!(
+            store.load(&c1).await.unwrap(),
+            HashSet::from([dj("Dixon", None, None)])
+        );
+    }
+
+    #[tokio::test]
+    async fn schema_and_history() {
+        let store = open_in_memory();
+        let tables: Vec<String> = {
+            let conn = store.conn.lock().unwrap();
+            let mut stmt = conn
+                .prepare("SELECT name FROM sqlite_master WHERE type = 'table' ORDER BY name")
+                .unwrap();
+            stmt.query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<rusqlite::Result<_>>()
+                .unwrap()
+        };
+        for table in [
+            "campaign_health",
+            "campaign_metadata",
+            "check_runs",
+            "checks",
+            "events",
+            "observations",
+            "outbox",
+            "supports",
+        ] {
+            assert!(tables.iter().any(|t| t == table), "missing table {}", table);
+        }
+
+        let c1 = campaign("c1");
+        assert!(store.load(&c1).await.unwrap().is_empty());
+        store
+            .save(
+                &c1,
+                &HashSet::from([dj("Dixon", None, None), dj("Kölsch", None, None)]),
+            )
+            .await
+            .unwrap();
+        store
+            .save(&c1, &HashSet::from([dj("Dixon", None, None)]))
+            .await
+            .unwrap();
+        // Other campaigns are kept apart
+        store
+            .save(&campaign("c2"), &HashSet::from([dj("Solomun", None, None)]))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.load(&c1).await.unwrap(),
+            HashSet::from([dj("Dixon", None, None)])
+        );
+        let history = store.history(&c1).await.unwrap();
+        let current: Vec<(&str, bool)> = history
+            .iter()
+            .map(|r| (r.dj.name.as_str(), r.current))
+            .collect();
+        assert_eq!(current.len(), 2);
+        assert!(current.contains(&("Dixon", true)));
+        assert!(current.contains(&("Kölsch", false)));
+        assert_eq!(store.snapshots(&c1).await.unwrap().len(), 2);
+        assert_eq!(
+            store.load_snapshot(&c1, "1").await.unwrap(),
+            HashSet::from([dj("Dixon", None, None), dj("Kölsch", None, None)])
+        );
+        assert!(store.load_snapshot(&c1, "3").await.is_err());
+        assert!(store.load_snapshot(&c1, "latest").await.is_err());
+
+        let event = SupportEvent {
+            kind: EventKind::RatingChanged,
+            dj: dj("Dixon", None, Some(5)),
+            previous: Some(dj("Dixon", None, Some(3))),
+            detected_at: "2025-01-15T10:30:00+00:00".to_string(),
+        };
+        store
+            .record_events(&c1, std::slice::from_ref(&event))
+            .await
+            .unwrap();
+        let events = store.events(&c1).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, EventKind::RatingChanged);
+        assert_eq!(events[0].dj, event.dj);
+        assert_eq!(events[0].previous, event.previous);
+        assert!(store.events(&campaign("c2")).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn retention_prunes_checks() {
+        let store = open_with_retention(RetentionPolicy {
+            max_count: Some(2),
+            max_age_days: None,
+        });
+        let c1 = campaign("c1");
+        for name in ["Dixon", "Kölsch", "Solomun"] {
+            store
+                .save(&c1, &HashSet::from([dj(name, None, None)]))
+                .await
+                .unwrap();
+        }
+        let ids: Vec<String> = store
+            .snapshots(&c1)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(ids, ["2", "3"]);
+        // Supports outlive the checks they were seen in
+        assert_eq!(store.history(&c1).await.unwrap().len(), 3);
+
+        let store = open_with_retention(RetentionPolicy {
+            max_count: None,
+            max_age_days: Some(30),
+        });
+        store
+            .conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO checks (campaign, checked_at, dj_count)
+                 VALUES ('c1', '2020-01-01T00:00:00+00:00', 0)",
+                [],
+            )
+            .unwrap();
+        store
+            .save(&c1, &HashSet::from([dj("Dixon", None, None)]))
+            .await
+            .unwrap();
+        let ids: Vec<String> = store
+            .snapshots(&c1)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(ids, ["2"]);
+    }
+
+    #[tokio::test]
+    async fn supports_follow_the_latest_listing() {
+        let store = open_in_memory();
+        let campaign = campaign("c1");
+
+        store
+            .save(
+                &campaign,
+                &HashSet::from([dj("Dixon", Some("Nice"), Some(3))]),
+            )
+            .await
+            .unwrap();
+        let first = store.snapshots(&campaign).await.unwrap()[0].id.clone();
+        store
+            .save(
+                &campaign,
+                &HashSet::from([dj("DJ Dixon", Some("Great"), Some(5))]),
+            )
+            .await
+            .unwrap();
+
+        // One support per DJ, with the current comment and rating
+        let history = store.history(&campaign).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].dj, dj("DJ Dixon", Some("Great"), Some(5)));
+        assert!(history[0].current);
+
+        // Past checks keep the DJ as listed at the time
+        assert_eq!(
+            store.load_snapshot(&campaign, &first).await.unwrap(),
+            HashSet::from([dj("Dixon", Some("Nice"), Some(3))])
+        );
+        assert_eq!(
+            store.load(&campaign).await.unwrap(),
+            HashSet::from([dj("DJ Dixon", Some("Great"), Some(5))])
+        );
+    }
+}
@@ -0,0 +1,468 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tracing::{debug, info, warn};
+
+use super::{DjStore, RetentionPolicy, SnapshotInfo, decode_dj_list, get_blob_name};
+use crate::diff::SupportEvent;
+use crate::metadata::CampaignMetadata;
+use crate::outbox::OutboxEntry;
+use crate::{Campaign, DjSupport};
+
+/// Format of snapshot IDs; sorts lexically in chronological order
+const SNAPSHOT_ID_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// Minimal object storage operations needed by [`VersionedStore`]
+#[async_trait]
+pub trait BlobBackend: Send + Sync {
+    /// Read an object, returning `None` if it doesn't exist
+    async fn get(&self, name: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Create or replace an object
+    async fn put(&self, name: &str, content: Vec<u8>) -> Result<()>;
+
+    /// Names of all objects starting with `prefix`
+    async fn list(&self, prefix: &str) -> Result<Vec<String>>;
+
+    /// Delete an object
+    async fn delete(&self, name: &str) -> Result<()>;
+}
+
+/// The current blob of a campaign, pointing at its latest snapshot
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotPointer {
+    snapshot: String,
+    taken_at: String,
+}
+
+/// An immutable copy of the DJ list as it was at one check
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    taken_at: String,
+    djs: HashSet<DjSupport>,
+}
+
+/// Writes every check as an immutable timestamped snapshot and keeps the campaign's
+/// current blob as a pointer to the latest one
+///
+/// Layout for campaign `pmqtne` with the default prefix:
+///
+/// * `dj_list_pmqtne.json` - pointer (`{"snapshot": "...", "taken_at": "..."}`)
+/// * `dj_list_pmqtne/snapshots/<id>.json` - one snapshot per check
+/// * `dj_list_pmqtne/events.json` - every detected change, oldest first
+/// * `dj_list_pmqtne/metadata.json` - release details scraped from the campaign page
+/// * `dj_list_pmqtne/health.json` - why the last check was suspect, if it was
+/// * `dj_list/outbox.json` - alerts of all campaigns waiting to be delivered; under the
+///   bare prefix, so no campaign's blobs can clash with it
+///
+/// Rolling back is a matter of editing the pointer to reference an older snapshot.
+/// Current blobs written before snapshots existed are read as a plain DJ list.
+pub struct VersionedStore<B> {
+    backend: B,
+    blob_name_prefix: String,
+    retention: RetentionPolicy,
+}
+
+impl<B: BlobBackend> VersionedStore<B> {
+    pub fn new(backend: B, blob_name_prefix: &str, retention: RetentionPolicy) -> Self {
+        VersionedStore {
+            backend,
+            blob_name_prefix: blob_name_prefix.to_string(),
+            retention,
+        }
+    }
+
+    fn snapshot_prefix(&self, campaign: &Campaign) -> String {
+        format!("{}_{}/snapshots/", self.blob_name_prefix, campaign.name)
+    }
+
+    fn events_name(&self, campaign: &Campaign) -> String {
+        format!("{}_{}/events.json", self.blob_name_prefix, campaign.name)
+    }
+
+    fn metadata_name(&self, campaign: &Campaign) -> String {
+        format!("{}_{}/metadata.json", self.blob_name_prefix, campaign.name)
+    }
+
+    fn health_name(&self, campaign: &Campaign) -> String {
+        format!("{}_{}/health.json", self.blob_name_prefix, campaign.name)
+    }
+
+    fn outbox_name(&self) -> String {
+        format!("{}/outbox.json", self.blob_name_prefix)
+    }
+
+    fn snapshot_name(&self, campaign: &Campaign, id: &str) -> String {
+        format!("{}{}.json", self.snapshot_prefix(campaign), id)
+    }
+
+    async fn read_snapshot(&self, name: &str) -> Result<HashSet<DjSupport>> {
+        let content = self
+            .backend
+            .get(name)
+            .await?
+            .with_context(|| format!("Snapshot {} does not exist", name))?;
+        let content_str =
+            String::from_utf8(content).context("Failed to parse snapshot content as UTF-8")?;
+        decode_dj_list(&content_str)
+    }
+
+    /// Delete snapshots that fall outside the retention policy, never touching `keep`
+    async fn prune(&self, campaign: &Campaign, keep: &str) -> Result<()> {
+        let mut snapshots = self.snapshots(campaign).await?;
+        // Newest first
+        snapshots.reverse();
+
+        let cutoff = self
+            .retention
+            .max_age_days
+            .map(|days| chrono::Utc::now() - chrono::Duration::days(days as i64));
+
+        for (index, snapshot) in snapshots.iter().enumerate() {
+            if snapshot.id == keep {
+                continue;
+            }
+
+            let over_count = self.retention.max_count.is_some_and(|max| index >= max);
+            let too_old = match (cutoff, parse_snapshot_id(&snapshot.id)) {
+                (Some(cutoff), Some(taken_at)) => taken_at < cutoff,
+                _ => false,
+            };
+
+            if over_count || too_old {
+                debug!(campaign = %campaign.name, snapshot = %snapshot.id, "Pruning snapshot");
+                self.backend
+                    .delete(&self.snapshot_name(campaign, &snapshot.id))
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_snapshot_id(id: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::NaiveDateTime::parse_from_str(id, SNAPSHOT_ID_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+#[async_trait]
+impl<B: BlobBackend> DjStore for VersionedStore<B> {
+    async fn load(&self, campaign: &Campaign) -> Result<HashSet<DjSupport>> {
+        let blob_name = get_blob_name(&self.blob_name_prefix, campaign);
+
+        let Some(content) = self.backend.get(&blob_name).await? else {
+            // Nothing saved yet (first run)
+            return Ok(HashSet::new());
+        };
+        let content_str =
+            String::from_utf8(content).context("Failed to parse blob content as UTF-8")?;
+
+        match serde_json::from_str::<SnapshotPointer>(&content_str) {
+            Ok(pointer) => {
+                self.read_snapshot(&self.snapshot_name(campaign, &pointer.snapshot))
+                    .await
+            }
+            // Written before snapshots existed; the blob holds the DJ list itself
+            Err(_) => decode_dj_list(&content_str),
+        }
+    }
+
+    async fn save(&self, campaign: &Campaign, djs: &HashSet<DjSupport>) -> Result<()> {
+        let now = chrono::Utc::now();
+        let id = now.format(SNAPSHOT_ID_FORMAT).to_string();
+        let taken_at = now.to_rfc3339();
+
+        let snapshot = Snapshot {
+            taken_at: taken_at.clone(),
+            djs: djs.clone(),
+        };
+        let json = serde_json::to_vec_pretty(&snapshot).context("Failed to serialize DJ list")?;
+        self.backend
+            .put(&self.snapshot_name(campaign, &id), json)
+            .await
+            .context("Failed to write snapshot")?;
+
+        // Only move the pointer once the snapshot is safely stored
+        let pointer = SnapshotPointer {
+            snapshot: id.clone(),
+            taken_at,
+        };
+        let json =
+            serde_json::to_vec_pretty(&pointer).context("Failed to serialize snapshot pointer")?;
+        self.backend
+            .put(&get_blob_name(&self.blob_name_prefix, campaign), json)
+            .await
+            .context("Failed to update snapshot pointer")?;
+
+        if self.retention.is_limited()
+            && let Err(e) = self.prune(campaign, &id).await
+        {
+            warn!(campaign = %campaign.name, error = %e, "Failed to prune old snapshots");
+        }
+
+        info!(campaign = %campaign.name, snapshot = %id, "Saved snapshot");
+        Ok(())
+    }
+
+    async fn snapshots(&self, campaign: &Campaign) -> Result<Vec<SnapshotInfo>> {
+        let prefix = self.snapshot_prefix(campaign);
+        let mut snapshots: Vec<SnapshotInfo> = self
+            .backend
+            .list(&prefix)
+            .await?
+            .into_iter()
+            .filter_map(|name| {
+                let id = name
+                    .strip_prefix(&prefix)?
+                    .strip_suffix(".json")?
+                    .to_string();
+                let taken_at = parse_snapshot_id(&id)?.to_rfc3339();
+                Some(SnapshotInfo { id, taken_at })
+            })
+            .collect();
+        snapshots.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(snapshots)
+    }
+
+    async fn load_snapshot(&self, campaign: &Campaign, id: &str) -> Result<HashSet<DjSupport>> {
+        if parse_snapshot_id(id).is_none() {
+            anyhow::bail!("Invalid snapshot ID: {}", id);
+        }
+        self.read_snapshot(&self.snapshot_name(campaign, id)).await
+    }
+
+    async fn record_events(&self, campaign: &Campaign, events: &[SupportEvent]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let mut log = self.events(campaign).await?;
+        log.extend_from_slice(events);
+
+        let json = serde_json::to_vec_pretty(&log).context("Failed to serialize event log")?;
+        self.backend
+            .put(&self.events_name(campaign), json)
+            .await
+            .context("Failed to write event log")
+    }
+
+    async fn events(&self, campaign: &Campaign) -> Result<Vec<SupportEvent>> {
+        match self.backend.get(&self.events_name(campaign)).await? {
+            Some(content) => {
+                serde_json::from_slice(&content).context("Failed to parse event log JSON")
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn load_metadata(&self, campaign: &Campaign) -> Result<Option<CampaignMetadata>> {
+        self.backend
+            .get(&self.metadata_name(campaign))
+            .await?
+            .map(|content| serde_json::from_slice(&content))
+            .transpose()
+            .context("Failed to parse campaign metadata JSON")
+    }
+
+    async fn save_metadata(&self, campaign: &Campaign, metadata: &CampaignMetadata) -> Result<()> {
+        let json =
+            serde_json::to_vec_pretty(metadata).context("Failed to serialize campaign metadata")?;
+        self.backend
+            .put(&self.metadata_name(campaign), json)
+            .await
+            .context("Failed to write campaign metadata")
+    }
+
+    async fn load_scrape_issues(&self, campaign: &Campaign) -> Result<Vec<String>> {
+        match self.backend.get(&self.health_name(campaign)).await? {
+            Some(content) => {
+                serde_json::from_slice(&content).context("Failed to parse scrape issues JSON")
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn save_scrape_issues(&self, campaign: &Campaign, issues: &[String]) -> Result<()> {
+        let json =
+            serde_json::to_vec_pretty(issues).context("Failed to serialize scrape issues")?;
+        self.backend
+            .put(&self.health_name(campaign), json)
+            .await
+            .context("Failed to write scrape issues")
+    }
+
+    async fn load_outbox(&self) -> Result<Vec<OutboxEntry>> {
+        match self.backend.get(&self.outbox_name()).await? {
+            Some(content) => {
+                serde_json::from_slice(&content).context("Failed to parse outbox JSON")
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn save_outbox(&self, entries: &[OutboxEntry]) -> Result<()> {
+        let json = serde_json::to_vec_pretty(entries).context("Failed to serialize outbox")?;
+        self.backend
+            .put(&self.outbox_name(), json)
+            .await
+            .context("Failed to write outbox")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::local::LocalDir;
+    use crate::testing::{self, dj};
+
+    fn campaign() -> Campaign {
+        testing::campaign("c1")
+    }
+
+    fn djs(names: &[&str]) -> HashSet<DjSupport> {
+        names.iter().map(|name| dj(name, None, None)).collect()
+    }
+
+    fn store(dir: &tempfile::TempDir, retention: RetentionPolicy) -> VersionedStore<LocalDir> {
+        VersionedStore::new(
+            LocalDir {
+                dir: dir.path().to_path_buf(),
+            },
+            "dj_list",
+            retention,
+        )
+    }
+
+    /// Write a snapshot as if it had been taken at the given time
+    async fn seed_snapshot(
+        store: &VersionedStore<LocalDir>,
+        taken_at: chrono::DateTime<chrono::Utc>,
+        names: &[&str],
+    ) -> String {
+        let id = taken_at.format(SNAPSHOT_ID_FORMAT).to_string();
+        let snapshot = Snapshot {
+            taken_at: taken_at.to_rfc3339(),
+            djs: djs(names),
+        };
+        store
+            .backend
+            .put(
+                &store.snapshot_name(&campaign(), &id),
+                serde_json::to_vec(&snapshot).unwrap(),
+            )
+            .await
+            .unwrap();
+        id
+    }
+
+    #[tokio::test]
+    async fn outbox_is_kept_apart_from_campaigns() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(&dir, RetentionPolicy::default());
+        let outbox = testing::campaign("outbox");
+
+        store.save(&outbox, &djs(&["Dixon"])).await.unwrap();
+        store.save_outbox(&[]).await.unwrap();
+        assert_eq!(store.load(&outbox).await.unwrap(), djs(&["Dixon"]));
+        assert!(store.load_outbox().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn snapshots_and_pointer() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(&dir, RetentionPolicy::default());
+        let campaign = campaign();
+
+        assert!(store.load(&campaign).await.unwrap().is_empty());
+        let earlier = seed_snapshot(
+            &store,
+            chrono::Utc::now() - chrono::Duration::hours(1),
+            &["Dixon"],
+        )
+        .await;
+        store
+            .save(&campaign, &djs(&["Dixon", "Kölsch"]))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.load(&campaign).await.unwrap(),
+            djs(&["Dixon", "Kölsch"])
+        );
+        let snapshots = store.snapshots(&campaign).await.unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].id, earlier);
+        assert_eq!(
+            store.load_snapshot(&campaign, &earlier).await.unwrap(),
+            djs(&["Dixon"])
+        );
+        assert!(store.load_snapshot(&campaign, "../outbox").await.is_err());
+
+        // Rolling back is a matter of pointing at an older snapshot
+        let pointer = SnapshotPointer {
+            snapshot: earlier,
+            taken_at: snapshots[0].taken_at.clone(),
+        };
+        std::fs::write(
+            dir.path().join("dj_list_c1.json"),
+            serde_json::to_vec(&pointer).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(store.load(&campaign).await.unwrap(), djs(&["Dixon"]));
+    }
+
+    #[tokio::test]
+    async fn reads_blobs_written_before_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(&dir, RetentionPolicy::default());
+        std::fs::write(dir.path().join("dj_list_c1.json"), r#"{"djs": ["Dixon"]}"#).unwrap();
+
+        assert_eq!(store.load(&campaign()).await.unwrap(), djs(&["Dixon"]));
+        assert!(store.snapshots(&campaign()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn retention_by_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(
+            &dir,
+            RetentionPolicy {
+                max_count: Some(2),
+                max_age_days: None,
+            },
+        );
+        let now = chrono::Utc::now();
+        seed_snapshot(&store, now - chrono::Duration::hours(3), &["A"]).await;
+        seed_snapshot(&store, now - chrono::Duration::hours(2), &["B"]).await;
+        let kept = seed_snapshot(&store, now - chrono::Duration::hours(1), &["C"]).await;
+        store.save(&campaign(), &djs(&["D"])).await.unwrap();
+
+        let snapshots = store.snapshots(&campaign()).await.unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].id, kept);
+        assert_eq!(store.load(&campaign()).await.unwrap(), djs(&["D"]));
+    }
+
+    #[tokio::test]
+    async fn retention_by_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(
+            &dir,
+            RetentionPolicy {
+                max_count: None,
+                max_age_days: Some(30),
+            },
+        );
+        let now = chrono::Utc::now();
+        seed_snapshot(&store, now - chrono::Duration::days(45), &["A"]).await;
+        let recent = seed_snapshot(&store, now - chrono::Duration::days(5), &["B"]).await;
+        store.save(&campaign(), &djs(&["C"])).await.unwrap();
+
+        let snapshots = store.snapshots(&campaign()).await.unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].id, recent);
+    }
+}
@@ -0,0 +1,39 @@
+//! Fixture factories shared by the unit tests
+
+use crate::diff::{EventKind, SupportEvent};
+use crate::{Campaign, DjSupport};
+
+/// When every fixture event was detected
+pub const DETECTED_AT: &str = "2025-01-15T10:30:00+00:00";
+
+/// A campaign at its Inflyte URL, with no metadata or settings
+pub fn campaign(name: &str) -> Campaign {
+    Campaign {
+        url: format!("https://inflyteapp.com/r/{}", name),
+        name: name.to_string(),
+        metadata: Default::default(),
+        settings: Default::default(),
+    }
+}
+
+pub fn dj(name: &str, comment: Option<&str>, stars: Option<u8>) -> DjSupport {
+    DjSupport {
+        name: name.to_string(),
+        comment: comment.map(str::to_string),
+        stars,
+    }
+}
+
+pub fn event(kind: EventKind, dj: DjSupport, previous: Option<DjSupport>) -> SupportEvent {
+    SupportEvent {
+        kind,
+        dj,
+        previous,
+        detected_at: DETECTED_AT.to_string(),
+    }
+}
+
+/// A newly listed DJ without comment or rating
+pub fn new_support(name: &str) -> SupportEvent {
+    event(EventKind::New, dj(name, None, None), None)
+}